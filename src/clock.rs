@@ -0,0 +1,133 @@
+//! Sources of the current time used by the loggers.
+//!
+//! Every built-in logger obtains the current time from a [Clock], the [SystemClock] by default.
+//! The macros use the logger's clock to timestamp each log and [FileLogger](crate::logger::FileLogger)
+//! uses it to decide when to start a new file with a [TimeRotation](crate::rotation::TimeRotation).
+//! Configuring a logger with a [FixedClock] or [MockClock] makes its output deterministic in
+//! tests without overriding the format's time.
+//!
+//! # Example
+//! ```
+//! use muxide_logging::clock::MockClock;
+//! use muxide_logging::format::Format;
+//! use muxide_logging::info;
+//! use muxide_logging::logger::StringLogger;
+//! use chrono::{Duration, TimeZone, Utc};
+//!
+//! let clock = MockClock::new(Utc.ymd(2021, 1, 1).and_hms(9, 30, 0));
+//! let mut logger = StringLogger::<Utc>::new_tz();
+//! logger.set_clock(clock.clone());
+//! logger.set_override(Format::parse("{time:%H:%M:%S} {message}").unwrap());
+//!
+//! assert_eq!(info!(logger: logger, "First").unwrap(), "09:30:00 First");
+//!
+//! clock.advance(Duration::seconds(5));
+//! assert_eq!(info!(logger: logger, "Second").unwrap(), "09:30:05 Second");
+//! ```
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A source of the current time.
+pub trait Clock: Debug + Send + Sync {
+    /// Get the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+/// The time reported by the operating system.
+pub struct SystemClock;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// A clock that is always at the same time.
+pub struct FixedClock {
+    time: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug)]
+/// A clock that only moves when it is [advanced](MockClock::advance) or [set](MockClock::set).
+/// Clones share the same time, so a clone can be kept to control the clock after it has been
+/// given to a logger.
+pub struct MockClock {
+    time: Arc<Mutex<DateTime<Utc>>>,
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        return Utc::now();
+    }
+}
+
+impl FixedClock {
+    /// Create a new [FixedClock] at `time`.
+    pub const fn new(time: DateTime<Utc>) -> Self {
+        return Self { time };
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        return self.time;
+    }
+}
+
+impl MockClock {
+    /// Create a new [MockClock] starting at `time`.
+    pub fn new(time: DateTime<Utc>) -> Self {
+        return Self {
+            time: Arc::new(Mutex::new(time)),
+        };
+    }
+
+    /// Move the clock forward by `duration`, or backwards if it is negative.
+    pub fn advance(&self, duration: Duration) {
+        let mut time = self.time.lock().unwrap_or_else(PoisonError::into_inner);
+        *time = *time + duration;
+    }
+
+    /// Move the clock to `time`.
+    pub fn set(&self, time: DateTime<Utc>) {
+        *self.time.lock().unwrap_or_else(PoisonError::into_inner) = time;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        return *self.time.lock().unwrap_or_else(PoisonError::into_inner);
+    }
+}
+
+/// The clock shared by a logger and its clones.
+pub(crate) type SharedClock = Arc<dyn Clock>;
+
+/// Create the clock used by loggers that haven't been given one.
+pub(crate) fn system_clock() -> SharedClock {
+    return Arc::new(SystemClock);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_mock_clock() {
+        let start = Utc.ymd(2021, 1, 1).and_hms(0, 0, 0);
+        let clock = MockClock::new(start);
+        let clone = clock.clone();
+
+        clone.advance(Duration::minutes(90));
+        assert_eq!(clock.now(), Utc.ymd(2021, 1, 1).and_hms(1, 30, 0));
+
+        clock.set(start);
+        assert_eq!(clone.now(), start);
+    }
+
+    #[test]
+    fn test_fixed_clock() {
+        let time = Utc.ymd(2003, 7, 1).and_hms(10, 52, 37);
+
+        assert_eq!(FixedClock::new(time).now(), time);
+    }
+}
@@ -0,0 +1,239 @@
+//! Per-module level directives, similar to the `RUST_LOG` variable used by other crates.
+
+use crate::log::{LogLevel, ParseLevelError};
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The environment variable read by [Directives::from_default_env].
+pub const DEFAULT_ENV_VAR: &str = "MUXIDE_LOG";
+
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+/// A set of minimum levels for modules, parsed from a comma separated list of directives such as
+/// `warning,muxide::pty=information`.
+///
+/// Each directive is one of:
+/// - `level`, the minimum level for any module without a more specific directive.
+/// - `module::path=level`, the minimum level for the module and its submodules.
+/// - `module::path`, enable every level for the module and its submodules.
+///
+/// When several directives match a module the one with the longest module path is used, so
+/// `muxide=error,muxide::pty=debug` allows debug logs from `muxide::pty::reader` but only errors
+/// from `muxide::render`.
+///
+/// # Example
+/// ```
+/// use muxide_logging::directive::Directives;
+/// use muxide_logging::log::LogLevel;
+///
+/// let directives = Directives::parse("warning,muxide::pty=information").unwrap();
+///
+/// assert_eq!(directives.level_for(Some("muxide::pty::reader")), Some(LogLevel::Information));
+/// assert_eq!(directives.level_for(Some("muxide::render")), Some(LogLevel::Warning));
+/// ```
+pub struct Directives {
+    default: Option<LogLevel>,
+    modules: Vec<(String, LogLevel)>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// An error produced when parsing [Directives].
+pub enum DirectiveParseError {
+    /// The level of a directive could not be parsed.
+    UnknownLevel {
+        directive: String,
+        error: ParseLevelError,
+    },
+    /// A directive had an empty module path, such as `=debug`.
+    EmptyModule { directive: String },
+}
+
+impl Directives {
+    /// Create an empty set of directives that matches no modules.
+    pub fn new() -> Self {
+        return Self {
+            default: None,
+            modules: Vec::new(),
+        };
+    }
+
+    /// Parse a comma separated list of directives, see [Directives].
+    pub fn parse(s: &str) -> Result<Self, DirectiveParseError> {
+        let mut directives = Self::new();
+
+        for directive in s.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.find('=') {
+                Some(idx) => {
+                    let module = directive[..idx].trim();
+
+                    if module.is_empty() {
+                        return Err(DirectiveParseError::EmptyModule {
+                            directive: directive.to_string(),
+                        });
+                    }
+
+                    let level = directive[idx + 1..].trim().parse().map_err(|error| {
+                        DirectiveParseError::UnknownLevel {
+                            directive: directive.to_string(),
+                            error,
+                        }
+                    })?;
+
+                    directives = directives.with_module(module, level);
+                }
+                None => match directive.parse() {
+                    Ok(level) => directives.default = Some(level),
+                    Err(_) => directives = directives.with_module(directive, LogLevel::Trace),
+                },
+            }
+        }
+
+        return Ok(directives);
+    }
+
+    /// Parse the directives in the environment variable `name`. Returns [None] if the variable is
+    /// not set or not valid unicode.
+    pub fn from_env(name: &str) -> Option<Result<Self, DirectiveParseError>> {
+        return std::env::var(name).ok().map(|value| Self::parse(&value));
+    }
+
+    /// Parse the directives in the [DEFAULT_ENV_VAR] environment variable.
+    pub fn from_default_env() -> Option<Result<Self, DirectiveParseError>> {
+        return Self::from_env(DEFAULT_ENV_VAR);
+    }
+
+    /// Set the minimum level for modules without a more specific directive.
+    pub fn with_default(mut self, level: LogLevel) -> Self {
+        self.default = Some(level);
+
+        return self;
+    }
+
+    /// Set the minimum level for a module and its submodules, replacing any existing directive
+    /// for the same module.
+    pub fn with_module(mut self, module: &str, level: LogLevel) -> Self {
+        match self.modules.iter_mut().find(|(m, _)| m == module) {
+            Some(directive) => directive.1 = level,
+            None => self.modules.push((module.to_string(), level)),
+        }
+
+        return self;
+    }
+
+    /// Get the minimum level for modules without a more specific directive.
+    pub fn default_level(&self) -> Option<LogLevel> {
+        return self.default;
+    }
+
+    /// Get the minimum level for `module_path` from the directive with the longest matching
+    /// module, falling back to the default level. A directive matches the module itself and any
+    /// of its submodules.
+    pub fn level_for(&self, module_path: Option<&str>) -> Option<LogLevel> {
+        let module_path = match module_path {
+            Some(module_path) => module_path,
+            None => return self.default,
+        };
+
+        return self
+            .modules
+            .iter()
+            .filter(|(module, _)| is_module_prefix(module, module_path))
+            .max_by_key(|(module, _)| module.len())
+            .map(|(_, level)| *level)
+            .or(self.default);
+    }
+
+    /// The least severe level allowed for any module, or [None] if there are no levels at all.
+    pub fn lowest_level(&self) -> Option<LogLevel> {
+        return self
+            .modules
+            .iter()
+            .map(|(_, level)| *level)
+            .chain(self.default)
+            .min();
+    }
+}
+
+/// Returns true if `module_path` is `prefix` or one of its submodules.
+fn is_module_prefix(prefix: &str, module_path: &str) -> bool {
+    return match module_path.strip_prefix(prefix) {
+        Some(rest) => rest.is_empty() || rest.starts_with("::"),
+        None => false,
+    };
+}
+
+impl FromStr for Directives {
+    type Err = DirectiveParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return Self::parse(s);
+    }
+}
+
+impl Display for DirectiveParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return match self {
+            DirectiveParseError::UnknownLevel { directive, error } => {
+                write!(f, "{} in directive '{}'", error, directive)
+            }
+            DirectiveParseError::EmptyModule { directive } => {
+                write!(f, "empty module in directive '{}'", directive)
+            }
+        };
+    }
+}
+
+impl std::error::Error for DirectiveParseError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::directive::{DirectiveParseError, Directives};
+    use crate::log::LogLevel;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Directives::parse(" warn , muxide::pty=Information,muxide::render ,").unwrap(),
+            Directives::new()
+                .with_default(LogLevel::Warning)
+                .with_module("muxide::pty", LogLevel::Information)
+                .with_module("muxide::render", LogLevel::Trace)
+        );
+        assert_eq!(Directives::parse("").unwrap(), Directives::new());
+        assert!(matches!(
+            Directives::parse("muxide=loud"),
+            Err(DirectiveParseError::UnknownLevel { .. })
+        ));
+        assert!(matches!(
+            Directives::parse("=debug"),
+            Err(DirectiveParseError::EmptyModule { .. })
+        ));
+    }
+
+    #[test]
+    fn test_longest_prefix() {
+        let directives =
+            Directives::parse("error,muxide=warning,muxide::pty=debug,muxide::pty::reader=trace")
+                .unwrap();
+
+        assert_eq!(directives.level_for(None), Some(LogLevel::Error));
+        assert_eq!(directives.level_for(Some("other")), Some(LogLevel::Error));
+        assert_eq!(
+            directives.level_for(Some("muxide")),
+            Some(LogLevel::Warning)
+        );
+        assert_eq!(
+            directives.level_for(Some("muxide::ptyx")),
+            Some(LogLevel::Warning)
+        );
+        assert_eq!(
+            directives.level_for(Some("muxide::pty::writer")),
+            Some(LogLevel::Debug)
+        );
+        assert_eq!(
+            directives.level_for(Some("muxide::pty::reader::buffer")),
+            Some(LogLevel::Trace)
+        );
+        assert_eq!(directives.lowest_level(), Some(LogLevel::Trace));
+        assert_eq!(Directives::new().level_for(Some("muxide")), None);
+    }
+}
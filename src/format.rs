@@ -1,8 +1,21 @@
 //! Formatting for logging messages
 
-use crate::log::LogLevel;
-use chrono::{DateTime, Local, TimeZone, Utc};
+use crate::json::JsonObject;
+use crate::layout::Layout;
+use crate::log::{Fields, LogLevel};
+use crate::process;
+use crate::strftime;
+use crate::style::{ColourMode, Palette, Style, RESET};
+use crate::timezone::{now, DefaultTimeZone};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, Offset, TimeZone, Timelike, Utc};
+use lazy_static::lazy_static;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::iter::Peekable;
 use std::ops::{Index, IndexMut};
+use std::str::{CharIndices, FromStr};
+use std::sync::{Arc, OnceLock};
+use std::thread::{self, Thread};
 
 #[derive(Clone, PartialEq, Debug)]
 /// A possible item type for used to dictate the format of a logged message.
@@ -13,8 +26,13 @@ pub enum FormatItem {
     ColumnNumber,
     /// The module of where the log macro was called.
     ModulePath,
+    /// The module of where the log macro was called, shortened with the specified
+    /// [ModulePathStyle].
+    ShortModulePath(ModulePathStyle),
     /// The File of where the log macro was called.
     File,
+    /// The name of the file where the log macro was called, without the directories leading to it.
+    FileName,
     /// The log level of the log.
     LogLevel,
     /// The message used to log.
@@ -25,6 +43,141 @@ pub enum FormatItem {
     CustomCharacter(char),
     ///  A custom string.
     CustomString(String),
+    /// The value of the structured field with the specified key, or nothing if the log has no such
+    /// field.
+    Field(String),
+    /// Every structured field not already displayed by a [FormatItem::Field] in the same format,
+    /// written as space separated `key=value` pairs.
+    Fields,
+    /// Start colouring output with the colour of the log level, taken from the format's
+    /// [Palette].
+    LevelColour,
+    /// Start applying a [Style] to the output.
+    Style(Style),
+    /// Reset any colours and styles.
+    Reset,
+    /// The id of the thread where the log macro was called.
+    ThreadId,
+    /// The name of the thread where the log macro was called, or its id if the thread is unnamed.
+    ThreadName,
+    /// The id of the process.
+    ProcessId,
+    /// The hostname of the machine.
+    Hostname,
+    /// The name of the running executable, without any extension.
+    ProgramName,
+    /// The [sequence number](crate::log::LogItem::sequence) of the log, strictly increasing in the
+    /// order logs are created.
+    Sequence,
+    /// The seconds between the logger starting and the log being created, with the specified
+    /// [Precision].
+    Elapsed(Precision),
+    /// The seconds between the previous log written by the same logger and this log being created,
+    /// with the specified [Precision]. Empty for the first log.
+    SinceLast(Precision),
+    /// Another item padded, aligned and truncated according to a [Layout], see
+    /// [FormatItem::with_layout].
+    Layout(Box<FormatItem>, Layout),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+/// The number of decimal places used by the [Elapsed](FormatItem::Elapsed) and
+/// [SinceLast](FormatItem::SinceLast) items when writing a number of seconds.
+pub enum Precision {
+    /// Whole seconds, `12`.
+    Seconds,
+    /// Milliseconds, `12.345`.
+    #[default]
+    Milliseconds,
+    /// Microseconds, `12.345678`.
+    Microseconds,
+    /// Nanoseconds, `12.345678901`.
+    Nanoseconds,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+/// How the [ShortModulePath](FormatItem::ShortModulePath) item shortens a module path such as
+/// `muxide::server::session::pane`.
+pub enum ModulePathStyle {
+    /// Every segment except the last is shortened to its first character, `m::s::s::pane`.
+    Abbreviated,
+    /// Only the specified number of trailing segments are kept, `session::pane` for 2.
+    Last(usize),
+    /// The specified leading segments are removed if the path starts with them, `session::pane`
+    /// for `muxide::server`. Paths that don't start with the prefix are kept in full.
+    StripPrefix(String),
+}
+
+/// The values from a log that a [Format] is built with.
+pub(crate) struct Record<'a, Tz: TimeZone> {
+    pub(crate) timestamp: DateTime<Tz>,
+    pub(crate) level: LogLevel,
+    pub(crate) message: &'a str,
+    pub(crate) fields: &'a Fields,
+    pub(crate) sequence: Option<u64>,
+    pub(crate) elapsed: Option<Duration>,
+    pub(crate) since_last: Option<Duration>,
+}
+
+/// The items and metadata of a format borrowed for building a log, see [Format::resolve].
+pub(crate) struct Resolved<'a, Tz: TimeZone> {
+    items: &'a [FormatItem],
+    line: Option<usize>,
+    column: Option<usize>,
+    file: Option<&'a str>,
+    module_path: Option<&'a str>,
+    thread_id: Option<usize>,
+    thread_name: Option<&'a str>,
+    custom_time: Option<DateTime<Tz>>,
+    palette: Option<Palette>,
+    colour_mode: ColourMode,
+}
+
+/// A time written in the RFC 3339 format, the same as
+/// [to_rfc3339](chrono::DateTime::to_rfc3339) but without building a [String].
+struct Rfc3339<Tz: TimeZone>(DateTime<Tz>);
+
+/// A duration written as a number of seconds with a [Precision].
+struct Seconds {
+    precision: Precision,
+    duration: Duration,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+/// The encoding used by a logger to turn a [Format] and log into a line of output.
+pub enum Encoding {
+    /// Plain text built from the sequence of [FormatItem]s, see [Format::build_string].
+    #[default]
+    Text,
+    /// A single JSON object per line (JSON Lines), see [Format::build_json].
+    Json,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// An error produced when parsing a pattern string with [Format::parse]. Every variant carries the
+/// byte offset into the pattern at which the problem was found.
+pub enum FormatParseError {
+    /// A placeholder name that does not correspond to any [FormatItem].
+    UnknownPlaceholder { offset: usize, name: String },
+    /// A placeholder that requires an argument, such as `{time:%H:%M}`, was supplied without one.
+    MissingArgument { offset: usize, name: String },
+    /// A placeholder that does not accept an argument was supplied with one.
+    UnexpectedArgument { offset: usize, name: String },
+    /// A placeholder was opened with `{` but never closed.
+    UnterminatedPlaceholder { offset: usize },
+    /// A single `{` or `}` that was not part of a placeholder. Literal braces are written as `{{`
+    /// and `}}`.
+    UnmatchedBrace { offset: usize },
+    /// A placeholder was supplied with an argument it does not understand, such as
+    /// `{style:loud}`.
+    InvalidArgument {
+        offset: usize,
+        name: String,
+        argument: String,
+    },
+    /// A placeholder was supplied with a [Layout] that could not be parsed, such as
+    /// `{level|wide}`.
+    InvalidLayout { offset: usize, layout: String },
 }
 
 #[derive(Clone, Debug)]
@@ -35,6 +188,11 @@ pub enum FormatItem {
 /// The default format is `[HH:MM:SS] (module_path line:column) log_level: log_message` and is
 /// created using the [Default] trait.
 ///
+/// The styling items [LevelColour](FormatItem::LevelColour), [Style](FormatItem::Style) and
+/// [Reset](FormatItem::Reset) produce ANSI escape codes depending on the format's [ColourMode], by
+/// default only when the `NO_COLOR` environment variable is not set. Loggers writing to files
+/// never produce escape codes, so the same format can be shared with a console logger.
+///
 /// # Example
 /// The default format.
 /// ```
@@ -62,84 +220,199 @@ where
     DateTime<Tz>: std::cmp::PartialEq,
     DateTime<Tz>: Copy,
 {
-    items: Vec<FormatItem>,
+    /// Shared between clones, so the macros don't copy the default items into each log.
+    items: Arc<Vec<FormatItem>>,
     column: Option<usize>,
     line: Option<usize>,
-    file: Option<String>,
-    module_path: Option<String>,
+    file: Origin,
+    module_path: Origin,
+    thread_id: Option<usize>,
+    thread_name: Origin,
     custom_time: Option<DateTime<Tz>>,
+    /// The timezone the current time is obtained in when there is no custom time.
+    timezone: Tz,
+    palette: Option<Palette>,
+    colour_mode: ColourMode,
+}
+
+#[derive(Clone, Debug, Default)]
+/// Optional text describing where a log originated. It is borrowed where possible so that the
+/// macros don't copy it into each log, and only copied if it is requested as a [String].
+struct Origin {
+    text: Option<Text>,
+    /// The copy handed out by the getters returning a [String].
+    owned: OnceLock<Option<String>>,
+}
+
+#[derive(Clone, Debug)]
+enum Text {
+    Owned(String),
+    Static(&'static str),
+    /// The name of a thread, read from its handle.
+    ThreadName(Thread),
+}
+
+lazy_static! {
+    /// The items of the default format, see [Format::default].
+    static ref DEFAULT_ITEMS: Arc<Vec<FormatItem>> = Arc::new(vec![
+        FormatItem::CustomCharacter('['),
+        FormatItem::TimeString("%k:%M:%S".to_string()),
+        FormatItem::CustomString("] (".to_string()),
+        FormatItem::ModulePath,
+        FormatItem::CustomCharacter(' '),
+        FormatItem::LineNumber,
+        FormatItem::CustomCharacter(':'),
+        FormatItem::ColumnNumber,
+        FormatItem::CustomString(") ".to_string()),
+        FormatItem::LogLevel,
+        FormatItem::CustomString(": ".to_string()),
+        FormatItem::LogString,
+    ]);
 }
 
 impl Format<Local> {
     /// Create a new empty [Format]
     pub fn new() -> Self {
-        return Self {
-            items: Vec::new(),
-            column: None,
-            line: None,
-            file: None,
-            module_path: None,
-            custom_time: None,
-        };
+        return Self::new_in(Local);
     }
 }
 
-impl<Tz: TimeZone> Format<Tz>
+impl<Tz: DefaultTimeZone> Format<Tz>
 where
     Tz::Offset: std::fmt::Display,
     DateTime<Tz>: Copy,
 {
     /// Create a new empty [Format] but for non-local timezones.
     pub fn new_tz() -> Self {
+        return Self::new_in(Tz::default_timezone());
+    }
+
+    /// Same as [default](Format::default) but with support for non-local timezones.
+    pub fn default_tz() -> Self {
+        return Self::default_in(Tz::default_timezone());
+    }
+
+    /// Parses a pattern string into a [Format]. Placeholders are wrapped in braces and any other
+    /// text is kept as-is, a literal brace is written as `{{` or `}}`.
+    ///
+    /// | Placeholder                | Item                                       |
+    /// |----------------------------|--------------------------------------------|
+    /// | `{line}`                   | [FormatItem::LineNumber]                   |
+    /// | `{column}`                 | [FormatItem::ColumnNumber]                 |
+    /// | `{module}`                 | [FormatItem::ModulePath]                   |
+    /// | `{module:<style>}`         | [FormatItem::ShortModulePath] with `style` |
+    /// | `{file}`                   | [FormatItem::File]                         |
+    /// | `{file:basename}`          | [FormatItem::FileName]                     |
+    /// | `{level}`                  | [FormatItem::LogLevel]                     |
+    /// | `{message}`                | [FormatItem::LogString]                    |
+    /// | `{time:<fmt>}`             | [FormatItem::TimeString] with `fmt`        |
+    /// | `{field:<key>}`            | [FormatItem::Field] with `key`             |
+    /// | `{fields}`                 | [FormatItem::Fields]                       |
+    /// | `{level_colour}`           | [FormatItem::LevelColour]                  |
+    /// | `{style:<name>}`           | [FormatItem::Style] with `name`            |
+    /// | `{reset}`                  | [FormatItem::Reset]                        |
+    /// | `{thread_id}`              | [FormatItem::ThreadId]                     |
+    /// | `{thread_name}`            | [FormatItem::ThreadName]                   |
+    /// | `{process_id}`             | [FormatItem::ProcessId]                    |
+    /// | `{hostname}`               | [FormatItem::Hostname]                     |
+    /// | `{program_name}`           | [FormatItem::ProgramName]                  |
+    /// | `{sequence}`               | [FormatItem::Sequence]                     |
+    /// | `{elapsed:<precision>}`    | [FormatItem::Elapsed] with `precision`     |
+    /// | `{since_last:<precision>}` | [FormatItem::SinceLast] with `precision`   |
+    /// | `{text:<text>}`            | [FormatItem::CustomString] with `text`     |
+    ///
+    /// The precision of `elapsed` and `since_last` is one of `s`, `ms`, `us` or `ns` and can be
+    /// left out to use milliseconds. The style of a shortened module is `abbreviated`, `last=<n>`
+    /// or `strip=<prefix>`, see [ModulePathStyle].
+    ///
+    /// Any placeholder can be given a [Layout] after a `|` as `[alignment][min_width][.max_width]`,
+    /// where the alignment is `<`, `>` or `^`. For example `{level|<7}` pads the level to seven
+    /// columns and `{module|>20.20}` right aligns the module path and truncates it to twenty
+    /// columns. A `|` within an argument is only read as the start of a layout if it is the last
+    /// one and a valid layout follows it, so `{time:%H|%M}` writes the time as `%H|%M`. A literal
+    /// `|` can always be written as `||` within an argument.
+    ///
+    /// Runs of literal text become a [FormatItem::CustomCharacter] if they are a single character
+    /// long and a [FormatItem::CustomString] otherwise. The [Display] implementation of [Format]
+    /// produces the same syntax so a parsed format can be printed and parsed again.
+    ///
+    /// # Example
+    /// ```
+    /// use chrono::Local;
+    /// use muxide_logging::format::Format;
+    ///
+    /// let pattern = "[{time:%k:%M:%S}] ({module} {line}:{column}) {level}: {message}";
+    /// let fmt = Format::<Local>::parse(pattern).unwrap();
+    ///
+    /// assert_eq!(fmt, Format::default());
+    /// assert_eq!(fmt.to_string(), pattern);
+    /// ```
+    pub fn parse(pattern: &str) -> Result<Self, FormatParseError> {
+        return Self::parse_in(pattern, Tz::default_timezone());
+    }
+}
+
+impl<Tz: TimeZone> Format<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    /// Create a new empty [Format] that obtains the current time in `timezone`, for timezones
+    /// such as [FixedOffset] that need configuring.
+    ///
+    /// # Example
+    /// ```
+    /// use chrono::FixedOffset;
+    /// use muxide_logging::format::Format;
+    /// use muxide_logging::log::LogLevel;
+    ///
+    /// let fmt = Format::parse_in("{time:%:z} {message}", FixedOffset::east(5 * 3600)).unwrap();
+    ///
+    /// assert_eq!(fmt.build_string(LogLevel::Error, "My Error"), "+05:00 My Error");
+    /// ```
+    pub fn new_in(timezone: Tz) -> Self {
+        return Self::with_items(timezone, Arc::new(Vec::new()));
+    }
+
+    /// Create a [Format] with `items` and no metadata.
+    fn with_items(timezone: Tz, items: Arc<Vec<FormatItem>>) -> Self {
         return Self {
-            items: Vec::new(),
+            items,
             column: None,
             line: None,
-            file: None,
-            module_path: None,
+            file: Origin::default(),
+            module_path: Origin::default(),
+            thread_id: None,
+            thread_name: Origin::default(),
             custom_time: None,
+            timezone,
+            palette: None,
+            colour_mode: ColourMode::Auto,
         };
     }
 
-    /// Same as [default](Format::default) but with support for non-local timezones.
-    pub fn default_tz() -> Self {
-        return crate::build_format_from_items_tz!(
-            FormatItem::CustomCharacter('['),
-            FormatItem::TimeString("%k:%M:%S".to_string()),
-            FormatItem::CustomString("] (".to_string()),
-            FormatItem::ModulePath,
-            FormatItem::CustomCharacter(' '),
-            FormatItem::LineNumber,
-            FormatItem::CustomCharacter(':'),
-            FormatItem::ColumnNumber,
-            FormatItem::CustomString(") ".to_string()),
-            FormatItem::LogLevel,
-            FormatItem::CustomString(": ".to_string()),
-            FormatItem::LogString
-        );
+    /// Same as [default](Format::default) but obtaining the current time in `timezone`.
+    pub fn default_in(timezone: Tz) -> Self {
+        return Self::with_items(timezone, DEFAULT_ITEMS.clone());
     }
 
     /// Create a new empty [Format] but with a custom constant time.
     pub fn new_with_constant_time(constant_time: DateTime<Tz>) -> Self {
-        return Self {
-            items: vec![],
-            column: None,
-            line: None,
-            file: None,
-            module_path: None,
-            custom_time: Some(constant_time),
-        };
+        let mut format = Self::new_in(constant_time.timezone());
+        format.custom_time = Some(constant_time);
+
+        return format;
     }
 
     /// Merges two 'Formats' into one, prioritising having a value over not having a value,
-    /// but where both contain a value preferring the values from 'a'.
+    /// but where both contain a value preferring the values from 'a'. The timezone of 'a' is
+    /// always used, a custom time from 'b' is converted into it.
     pub fn merged<T1: TimeZone>(a: &Format<Tz>, b: &Format<T1>) -> Format<Tz>
     where
         T1::Offset: std::fmt::Display,
         DateTime<T1>: Copy,
-        DateTime<T1>: Into<DateTime<Tz>>,
     {
-        let items = if a.items.len() == 0 {
+        let items = if a.items.is_empty() {
             b.items.clone()
         } else {
             a.items.clone()
@@ -165,10 +438,34 @@ where
             a.module_path.clone()
         };
 
+        let thread_id = if a.thread_id.is_none() {
+            b.thread_id
+        } else {
+            a.thread_id
+        };
+
+        let thread_name = if a.thread_name.is_none() {
+            b.thread_name.clone()
+        } else {
+            a.thread_name.clone()
+        };
+
         let custom_time: Option<DateTime<Tz>> = if a.custom_time.is_none() {
-            b.custom_time.as_ref().map(|t| t.clone().into())
+            b.custom_time.map(|t| t.with_timezone(&a.timezone))
         } else {
-            a.custom_time.clone()
+            a.custom_time
+        };
+
+        let palette = if a.palette.is_none() {
+            b.palette
+        } else {
+            a.palette
+        };
+
+        let colour_mode = if a.colour_mode == ColourMode::Auto {
+            b.colour_mode
+        } else {
+            a.colour_mode
         };
 
         return Format {
@@ -177,58 +474,304 @@ where
             line,
             file,
             module_path,
+            thread_id,
+            thread_name,
             custom_time,
+            timezone: a.timezone.clone(),
+            palette,
+            colour_mode,
         };
     }
 
+    /// Same as [parse](Format::parse) but obtaining the current time in `timezone`.
+    pub fn parse_in(pattern: &str, timezone: Tz) -> Result<Self, FormatParseError> {
+        let mut format = Self::new_in(timezone);
+        let mut literal = String::new();
+        let mut chars = pattern.char_indices().peekable();
+
+        while let Some((offset, ch)) = chars.next() {
+            match ch {
+                '{' if next_is(&mut chars, '{') => literal.push('{'),
+                '}' if next_is(&mut chars, '}') => literal.push('}'),
+                '{' => {
+                    format.push_literal(&mut literal);
+
+                    let (name, argument, layout) = parse_placeholder(&mut chars, offset)?;
+                    let item = FormatItem::from_placeholder(name, argument, offset)?;
+
+                    Arc::make_mut(&mut format.items).push(match layout {
+                        Some(layout) => match layout.parse() {
+                            Ok(parsed) => item.with_layout(parsed),
+                            Err(_) => {
+                                return Err(FormatParseError::InvalidLayout { offset, layout })
+                            }
+                        },
+                        None => item,
+                    });
+                }
+                '}' => return Err(FormatParseError::UnmatchedBrace { offset }),
+                _ => literal.push(ch),
+            }
+        }
+
+        format.push_literal(&mut literal);
+
+        return Ok(format);
+    }
+
+    /// Appends the pending literal text as a custom item and clears it.
+    fn push_literal(&mut self, literal: &mut String) {
+        let mut chars = literal.chars();
+
+        match (chars.next(), chars.next()) {
+            (None, _) => return,
+            (Some(ch), None) => {
+                Arc::make_mut(&mut self.items).push(FormatItem::CustomCharacter(ch))
+            }
+            _ => Arc::make_mut(&mut self.items).push(FormatItem::CustomString(literal.clone())),
+        }
+
+        literal.clear();
+    }
+
     /// Consumes the format object and builds the formatted output from the log level and log
     /// message.
     pub fn build_string(self, log_level: LogLevel, log_message: &str) -> String {
-        let mut item_strings = Vec::with_capacity(self.items.len());
+        return self.build_string_with_fields(log_level, log_message, &Fields::new());
+    }
 
-        for item in self.items {
-            let string = match item {
-                FormatItem::LineNumber => {
-                    if self.line.is_some() {
-                        self.line.unwrap().to_string()
-                    } else {
-                        String::new()
-                    }
-                }
-                FormatItem::ColumnNumber => {
-                    if self.column.is_some() {
-                        self.column.unwrap().to_string()
-                    } else {
-                        String::new()
-                    }
-                }
-                FormatItem::ModulePath => self
-                    .module_path
-                    .as_ref()
-                    .map(|s| s.clone())
-                    .unwrap_or(String::new()),
-                FormatItem::LogLevel => log_level.to_string(),
-                FormatItem::LogString => log_message.to_string(),
-                FormatItem::TimeString(fmt_string) => {
-                    if self.custom_time.is_some() {
-                        self.custom_time.unwrap().format(&fmt_string).to_string()
-                    } else {
-                        Local::now().format(&fmt_string).to_string()
-                    }
-                }
-                FormatItem::CustomCharacter(ch) => ch.to_string(),
-                FormatItem::CustomString(s) => s,
-                FormatItem::File => self
-                    .file
-                    .as_ref()
-                    .map(|s| s.clone())
-                    .unwrap_or(String::new()),
-            };
+    /// Same as [build_string](Format::build_string) but with the structured fields used by
+    /// [FormatItem::Field] and [FormatItem::Fields].
+    pub fn build_string_with_fields(
+        self,
+        log_level: LogLevel,
+        log_message: &str,
+        fields: &Fields,
+    ) -> String {
+        let record = Record::new(now(&self.timezone), log_level, log_message, fields);
 
-            item_strings.push(string);
-        }
+        return self.build_record(Encoding::Text, &record);
+    }
+
+    /// Writes the output for the log level and log message to `writer`, the same as
+    /// [build_string](Format::build_string) but borrowing the format and writing each item
+    /// directly rather than building a [String].
+    ///
+    /// Writing a format allocates nothing apart from a [Layout](FormatItem::Layout), which builds
+    /// the item it wraps in a temporary string to measure it.
+    ///
+    /// # Example
+    /// ```
+    /// use chrono::Utc;
+    /// use muxide_logging::format::Format;
+    /// use muxide_logging::log::LogLevel;
+    ///
+    /// let fmt = Format::<Utc>::parse("{level}: {message}").unwrap();
+    /// let mut buffer = String::new();
+    ///
+    /// fmt.write_to(&mut buffer, LogLevel::Error, "First").unwrap();
+    /// buffer.clear();
+    /// fmt.write_to(&mut buffer, LogLevel::Error, "Second").unwrap();
+    ///
+    /// assert_eq!(buffer, "Error: Second");
+    /// ```
+    pub fn write_to<W: fmt::Write>(
+        &self,
+        writer: &mut W,
+        log_level: LogLevel,
+        log_message: &str,
+    ) -> fmt::Result {
+        let fields = Fields::new();
+        let record = Record::new(now(&self.timezone), log_level, log_message, &fields);
+
+        return self.resolve(None).write_text(writer, &record);
+    }
+
+    /// Same as [write_to](Format::write_to) but writing to an [io::Write], such as a file or
+    /// [Stdout](std::io::Stdout).
+    pub fn write_io<W: io::Write>(
+        &self,
+        writer: &mut W,
+        log_level: LogLevel,
+        log_message: &str,
+    ) -> io::Result<()> {
+        let mut writer = IoWriter {
+            inner: writer,
+            error: None,
+        };
+
+        return match self.write_to(&mut writer, log_level, log_message) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(writer
+                .error
+                .unwrap_or_else(|| io::Error::other("formatter error"))),
+        };
+    }
+
+    /// Consumes the format object and builds a single line JSON object from the log level, log
+    /// message and fields. The sequence of [FormatItem]s, including any styling, is ignored,
+    /// instead the object contains
+    /// the `timestamp` (RFC 3339), `level`, `message`, `module_path`, `file`, `line` and `column`
+    /// keys, using `null` for any unset values, followed by a `fields` object if there are any
+    /// structured fields.
+    ///
+    /// The `process_id`, `hostname`, `program_name`, `sequence`, `elapsed` and `since_last` keys
+    /// are added before the fields when the format contains the [ProcessId](FormatItem::ProcessId),
+    /// [Hostname](FormatItem::Hostname), [ProgramName](FormatItem::ProgramName),
+    /// [Sequence](FormatItem::Sequence), [Elapsed](FormatItem::Elapsed) or
+    /// [SinceLast](FormatItem::SinceLast) items respectively. The sequence and times are only
+    /// known when building a [LogItem](crate::log::LogItem), otherwise they are `null`.
+    ///
+    /// # Example
+    /// ```
+    /// use chrono::{DateTime, Utc};
+    /// use muxide_logging::format::Format;
+    /// use muxide_logging::log::{Fields, LogLevel};
+    ///
+    /// let fmt = Format::<Utc>::new_tz()
+    ///     .set_line(12)
+    ///     .set_module_path("my_crate::file")
+    ///     .set_constant_time(DateTime::from(
+    ///         DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
+    ///     ));
+    ///
+    /// assert_eq!(
+    ///     fmt.build_json(LogLevel::Error, "My \"Error\"", &Fields::new()),
+    ///     r#"{"timestamp":"2003-07-01T10:52:37+00:00","level":"Error","message":"My \"Error\"","module_path":"my_crate::file","file":null,"line":12,"column":null}"#
+    /// );
+    /// ```
+    pub fn build_json(self, log_level: LogLevel, log_message: &str, fields: &Fields) -> String {
+        let record = Record::new(now(&self.timezone), log_level, log_message, fields);
+
+        return self.build_record(Encoding::Json, &record);
+    }
+
+    /// Consumes the format object and builds the output using the specified [Encoding].
+    pub fn build(
+        self,
+        encoding: Encoding,
+        log_level: LogLevel,
+        log_message: &str,
+        fields: &Fields,
+    ) -> String {
+        let timestamp = now(&self.timezone);
+
+        return self.build_at(timestamp, encoding, log_level, log_message, fields);
+    }
+
+    /// Same as [build](Format::build) but with `timestamp` as the time of the log instead of the
+    /// current time, such as the time a [LogItem](crate::log::LogItem) was created. A constant
+    /// time set with [set_constant_time](Format::set_constant_time) still takes precedence.
+    pub fn build_at(
+        self,
+        timestamp: DateTime<Tz>,
+        encoding: Encoding,
+        log_level: LogLevel,
+        log_message: &str,
+        fields: &Fields,
+    ) -> String {
+        let record = Record::new(timestamp, log_level, log_message, fields);
+
+        return self.build_record(encoding, &record);
+    }
+
+    /// Builds the output from the values of a log using the specified [Encoding].
+    pub(crate) fn build_record(&self, encoding: Encoding, record: &Record<Tz>) -> String {
+        let mut output = String::new();
+        self.resolve(None).write(encoding, &mut output, record);
+
+        return output;
+    }
+
+    /// Borrow the items and metadata of the format for building, preferring any items and
+    /// metadata set by `override_format` in the same way as [merged](Format::merged).
+    pub(crate) fn resolve<'a>(
+        &'a self,
+        override_format: Option<&'a Format<Tz>>,
+    ) -> Resolved<'a, Tz> {
+        let a = override_format.unwrap_or(self);
+        let b = self;
+
+        return Resolved {
+            items: if a.items.is_empty() {
+                &b.items
+            } else {
+                &a.items
+            },
+            line: a.line.or(b.line),
+            column: a.column.or(b.column),
+            file: a.file.as_str().or(b.file.as_str()),
+            module_path: a.module_path.as_str().or(b.module_path.as_str()),
+            thread_id: a.thread_id.or(b.thread_id),
+            thread_name: a.thread_name.as_str().or(b.thread_name.as_str()),
+            custom_time: a
+                .custom_time
+                .or_else(|| b.custom_time.map(|t| t.with_timezone(&a.timezone))),
+            palette: a.palette.or(b.palette),
+            colour_mode: if a.colour_mode == ColourMode::Auto {
+                b.colour_mode
+            } else {
+                a.colour_mode
+            },
+        };
+    }
+
+    /// Convert the format into one with a fixed offset timezone, keeping the offset of any
+    /// constant time. Without a constant time the current offset of the timezone is used.
+    pub fn into_fixed_offset(self) -> Format<FixedOffset> {
+        let offset = match self.custom_time {
+            Some(time) => time.offset().fix(),
+            None => now(&self.timezone).offset().fix(),
+        };
+
+        return self.with_timezone::<FixedOffset>(offset);
+    }
+
+    /// Convert the format into one using `timezone`, converting any constant time into it.
+    pub fn with_timezone<T2: TimeZone>(self, timezone: T2) -> Format<T2>
+    where
+        T2::Offset: std::fmt::Display,
+        DateTime<T2>: Copy,
+    {
+        return Format::<T2> {
+            items: self.items,
+            column: self.column,
+            line: self.line,
+            file: self.file,
+            module_path: self.module_path,
+            thread_id: self.thread_id,
+            thread_name: self.thread_name,
+            custom_time: self.custom_time.map(|time| time.with_timezone(&timezone)),
+            timezone,
+            palette: self.palette,
+            colour_mode: self.colour_mode,
+        };
+    }
+
+    /// Same as [with_timezone](Format::with_timezone) but without consuming the format.
+    pub(crate) fn to_timezone<T2: TimeZone>(&self, timezone: T2) -> Format<T2>
+    where
+        T2::Offset: std::fmt::Display,
+        DateTime<T2>: Copy,
+    {
+        return Format::<T2> {
+            items: self.items.clone(),
+            column: self.column,
+            line: self.line,
+            file: self.file.clone(),
+            module_path: self.module_path.clone(),
+            thread_id: self.thread_id,
+            thread_name: self.thread_name.clone(),
+            custom_time: self.custom_time.map(|time| time.with_timezone(&timezone)),
+            timezone,
+            palette: self.palette,
+            colour_mode: self.colour_mode,
+        };
+    }
 
-        return item_strings.join("");
+    /// Get the timezone the current time is obtained in.
+    pub fn timezone(&self) -> &Tz {
+        return &self.timezone;
     }
 
     /// Set the column where the log originated.
@@ -257,26 +800,98 @@ where
 
     /// Set the file where the log originated.
     pub fn set_file(mut self, file: &str) -> Self {
-        self.file = Some(file.to_string());
+        self.file = Origin::new(Text::Owned(file.to_string()));
+
+        return self;
+    }
+
+    /// Same as [set_file](Format::set_file) but borrowing a static string, such as one from
+    /// [file!], rather than copying it.
+    pub fn set_static_file(mut self, file: &'static str) -> Self {
+        self.file = Origin::new(Text::Static(file));
 
         return self;
     }
 
     /// Get the file where the log originated.
     pub fn file(&self) -> &Option<String> {
-        return &self.file;
+        return self.file.as_string();
+    }
+
+    /// Same as [file](Format::file) but borrowing the file, rather than copying one set with
+    /// [set_static_file](Format::set_static_file).
+    pub fn file_str(&self) -> Option<&str> {
+        return self.file.as_str();
     }
 
     /// Set the module where the log originated.
     pub fn set_module_path(mut self, path: &str) -> Self {
-        self.module_path = Some(path.to_string());
+        self.module_path = Origin::new(Text::Owned(path.to_string()));
+
+        return self;
+    }
+
+    /// Same as [set_module_path](Format::set_module_path) but borrowing a static string, such as
+    /// one from [module_path!], rather than copying it.
+    pub fn set_static_module_path(mut self, path: &'static str) -> Self {
+        self.module_path = Origin::new(Text::Static(path));
 
         return self;
     }
 
     /// Get the module where the log originated.
     pub fn module_path(&self) -> &Option<String> {
-        return &self.module_path;
+        return self.module_path.as_string();
+    }
+
+    /// Same as [module_path](Format::module_path) but borrowing the module, rather than copying
+    /// one set with [set_static_module_path](Format::set_static_module_path).
+    pub fn module_path_str(&self) -> Option<&str> {
+        return self.module_path.as_str();
+    }
+
+    /// Set the id of the thread where the log originated.
+    pub fn set_thread_id(mut self, id: usize) -> Self {
+        self.thread_id = Some(id);
+
+        return self;
+    }
+
+    /// Get the id of the thread where the log originated.
+    pub fn thread_id(&self) -> Option<usize> {
+        return self.thread_id;
+    }
+
+    /// Set the name of the thread where the log originated.
+    pub fn set_thread_name(mut self, name: &str) -> Self {
+        self.thread_name = Origin::new(Text::Owned(name.to_string()));
+
+        return self;
+    }
+
+    /// Get the name of the thread where the log originated.
+    pub fn thread_name(&self) -> &Option<String> {
+        return self.thread_name.as_string();
+    }
+
+    /// Same as [thread_name](Format::thread_name) but borrowing the name, rather than copying
+    /// one set with [set_current_thread](Format::set_current_thread).
+    pub fn thread_name_str(&self) -> Option<&str> {
+        return self.thread_name.as_str();
+    }
+
+    /// Set the id and, if it has one, the name of the current thread as where the log originated.
+    /// This is used by the macros so that the thread is still known if the log is built on
+    /// another thread.
+    pub fn set_current_thread(mut self) -> Self {
+        let current = thread::current();
+        self.thread_id = Some(thread_id::get());
+        self.thread_name = match current.name() {
+            Some(_) => Origin::new(Text::ThreadName(current)),
+            None => Origin::default(),
+        };
+
+        return self;
     }
 
     /// Set a custom time to override the current time.
@@ -293,127 +908,1044 @@ where
         return self;
     }
 
+    /// Get the custom time overriding the time of each log, if any.
+    pub fn constant_time(&self) -> Option<DateTime<Tz>> {
+        return self.custom_time;
+    }
+
+    /// Set the [Palette] used by [FormatItem::LevelColour].
+    pub fn set_palette(mut self, palette: Palette) -> Self {
+        self.palette = Some(palette);
+
+        return self;
+    }
+
+    /// Get the [Palette] used by [FormatItem::LevelColour], if one has been set.
+    pub fn palette(&self) -> Option<Palette> {
+        return self.palette;
+    }
+
+    /// Set whether the styling items produce escape codes.
+    pub fn set_colour_mode(mut self, mode: ColourMode) -> Self {
+        self.colour_mode = mode;
+
+        return self;
+    }
+
+    /// Get whether the styling items produce escape codes.
+    pub fn colour_mode(&self) -> ColourMode {
+        return self.colour_mode;
+    }
+
     /// Append a [FormatItem] to the current sequence.
     pub fn append(mut self, item: FormatItem) -> Self {
-        self.items.push(item);
+        Arc::make_mut(&mut self.items).push(item);
 
         return self;
     }
 
     /// Remove the last [FormatItem] from the sequence.
     pub fn pop_last(mut self) -> Self {
-        let _ = self.items.pop();
+        let _ = Arc::make_mut(&mut self.items).pop();
 
         return self;
     }
 }
 
-impl<Tz: TimeZone> Index<usize> for Format<Tz>
+impl<'a, Tz: TimeZone> Resolved<'a, Tz>
 where
     Tz::Offset: std::fmt::Display,
     DateTime<Tz>: Copy,
 {
-    type Output = FormatItem;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        return self.items.index(index);
+    /// Get whether styling items produce escape codes.
+    pub(crate) fn colour_mode(&self) -> ColourMode {
+        return self.colour_mode;
     }
-}
 
-impl<Tz: TimeZone> IndexMut<usize> for Format<Tz>
-where
-    Tz::Offset: std::fmt::Display,
-    DateTime<Tz>: Copy,
-{
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        return self.items.index_mut(index);
+    /// Set whether styling items produce escape codes.
+    pub(crate) fn set_colour_mode(&mut self, mode: ColourMode) {
+        self.colour_mode = mode;
     }
-}
 
-impl<Tz: TimeZone> PartialEq for Format<Tz>
-where
-    Tz::Offset: std::fmt::Display,
-    DateTime<Tz>: Copy + PartialEq,
-{
-    fn eq(&self, other: &Self) -> bool {
-        return self.file == other.file
-            && self.custom_time == other.custom_time
-            && self.module_path == other.module_path
-            && self.column == other.column
-            && self.line == other.line
-            && self.items == other.items;
+    /// Append the output built from the values of a log with the specified [Encoding] to
+    /// `buffer`.
+    pub(crate) fn write(&self, encoding: Encoding, buffer: &mut String, record: &Record<Tz>) {
+        match encoding {
+            Encoding::Text => {
+                // Writing to a String never fails.
+                let _ = self.write_text(buffer, record);
+            }
+            Encoding::Json => self.write_json(buffer, record),
+        }
     }
-}
 
-impl Default for Format<Local> {
-    /// Creates a new instance of [Format] with the format
-    /// `[HH:MM:SS] (module_path line:column) log_level: log_message`
-    fn default() -> Self {
-        return crate::build_format_from_items!(
-            FormatItem::CustomCharacter('['),
-            FormatItem::TimeString("%k:%M:%S".to_string()),
-            FormatItem::CustomString("] (".to_string()),
-            FormatItem::ModulePath,
-            FormatItem::CustomCharacter(' '),
-            FormatItem::LineNumber,
-            FormatItem::CustomCharacter(':'),
-            FormatItem::ColumnNumber,
-            FormatItem::CustomString(") ".to_string()),
-            FormatItem::LogLevel,
-            FormatItem::CustomString(": ".to_string()),
-            FormatItem::LogString
-        );
-    }
-}
+    /// Writes the text output from the values of a log to `writer`.
+    fn write_text<W: fmt::Write>(&self, writer: &mut W, record: &Record<Tz>) -> fmt::Result {
+        let time = self.custom_time.unwrap_or(record.timestamp);
+        let colour = self.items.iter().any(FormatItem::is_styling) && self.colour_mode.is_enabled();
 
-impl From<Format<Local>> for Format<Utc> {
-    fn from(fmt: Format<Local>) -> Self {
-        return Self {
-            items: fmt.items,
-            column: fmt.column,
-            line: fmt.line,
-            file: fmt.file,
-            module_path: fmt.module_path,
-            custom_time: fmt.custom_time.map(|dt| dt.into()),
-        };
+        for item in self.items {
+            self.write_item(writer, item, record, &time, colour)?;
+        }
+
+        return Ok(());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::format::{Format, FormatItem};
-    use crate::log::LogLevel;
-    use chrono::{DateTime, Utc};
+    /// Writes the output of a single item to `writer`.
+    fn write_item<W: fmt::Write>(
+        &self,
+        writer: &mut W,
+        item: &FormatItem,
+        record: &Record<Tz>,
+        time: &DateTime<Tz>,
+        colour: bool,
+    ) -> fmt::Result {
+        return match item {
+            FormatItem::LineNumber => write_optional(writer, self.line),
+            FormatItem::ColumnNumber => write_optional(writer, self.column),
+            FormatItem::ModulePath => write_optional(writer, self.module_path),
+            FormatItem::ShortModulePath(style) => match self.module_path {
+                Some(path) => style.write(writer, path),
+                None => Ok(()),
+            },
+            FormatItem::ThreadId => write_optional(writer, self.thread_id),
+            FormatItem::ThreadName => match self.thread_name {
+                Some(name) => writer.write_str(name),
+                None => write_optional(writer, self.thread_id),
+            },
+            FormatItem::ProcessId => write!(writer, "{}", process::process_id()),
+            FormatItem::Hostname => writer.write_str(process::hostname()),
+            FormatItem::ProgramName => writer.write_str(process::program_name()),
+            FormatItem::Sequence => write_optional(writer, record.sequence),
+            FormatItem::Elapsed(precision) => write_optional(
+                writer,
+                record.elapsed.map(|elapsed| precision.seconds(elapsed)),
+            ),
+            FormatItem::SinceLast(precision) => write_optional(
+                writer,
+                record
+                    .since_last
+                    .map(|since_last| precision.seconds(since_last)),
+            ),
+            FormatItem::LogLevel => writer.write_str(record.level.as_str()),
+            FormatItem::LogString => writer.write_str(record.message),
+            FormatItem::TimeString(fmt_string) => strftime::write_time(writer, time, fmt_string),
+            FormatItem::CustomCharacter(ch) => writer.write_char(*ch),
+            FormatItem::CustomString(s) => writer.write_str(s),
+            FormatItem::File => write_optional(writer, self.file),
+            FormatItem::FileName => write_optional(writer, self.file.map(file_name)),
+            FormatItem::Field(key) => write_optional(writer, record.fields.get(key)),
+            FormatItem::Fields => {
+                let unnamed = record
+                    .fields
+                    .iter()
+                    .filter(|(key, _)| !self.names_field(key));
 
-    #[test]
-    fn test_default() {
-        assert_eq!(
-            Format::default(),
-            Format {
-                items: vec![
-                    FormatItem::CustomCharacter('['),
-                    FormatItem::TimeString("%k:%M:%S".to_string()),
-                    FormatItem::CustomString("] (".to_string()),
-                    FormatItem::ModulePath,
-                    FormatItem::CustomCharacter(' '),
-                    FormatItem::LineNumber,
-                    FormatItem::CustomCharacter(':'),
-                    FormatItem::ColumnNumber,
-                    FormatItem::CustomString(") ".to_string()),
-                    FormatItem::LogLevel,
-                    FormatItem::CustomString(": ".to_string()),
-                    FormatItem::LogString
-                ],
-                column: None,
-                line: None,
-                file: None,
-                module_path: None,
-                custom_time: None
+                for (idx, (key, value)) in unnamed.enumerate() {
+                    if idx > 0 {
+                        writer.write_char(' ')?;
+                    }
+
+                    write!(writer, "{}=", key)?;
+                    value.write_pair(writer)?;
+                }
+
+                Ok(())
             }
-        )
-    }
+            FormatItem::LevelColour if colour => {
+                let palette = self.palette.unwrap_or_default();
 
-    #[test]
+                Style::Colour(palette.colour(record.level)).write_escape_code(writer)
+            }
+            FormatItem::Style(style) if colour => style.write_escape_code(writer),
+            FormatItem::Reset if colour => writer.write_str(RESET),
+            FormatItem::LevelColour | FormatItem::Style(_) | FormatItem::Reset => Ok(()),
+            FormatItem::Layout(inner, layout) => {
+                // The wrapped item is measured before it is written, so it can't be streamed.
+                let mut text = String::new();
+                self.write_item(&mut text, inner, record, time, colour)?;
+
+                layout.write(writer, &text, inner.truncates_left())
+            }
+        };
+    }
+
+    /// Returns true if the format has a [Field](FormatItem::Field) item for `key`.
+    fn names_field(&self, key: &str) -> bool {
+        return self
+            .items
+            .iter()
+            .any(|item| matches!(item.unwrapped(), FormatItem::Field(name) if name == key));
+    }
+
+    /// Writes the JSON output from the values of a log to `buffer`.
+    fn write_json(&self, buffer: &mut String, record: &Record<Tz>) {
+        let timestamp = self.custom_time.unwrap_or(record.timestamp);
+
+        let mut object = JsonObject::new(buffer);
+        object.display("timestamp", Rfc3339(timestamp));
+        object.string("level", record.level.as_str());
+        object.string("message", record.message);
+        object.optional_string("module_path", self.module_path);
+        object.optional_string("file", self.file);
+        object.optional_number("line", self.line);
+        object.optional_number("column", self.column);
+
+        if self.contains(&FormatItem::ProcessId) {
+            object.number("process_id", process::process_id());
+        }
+
+        if self.contains(&FormatItem::Hostname) {
+            object.string("hostname", process::hostname());
+        }
+
+        if self.contains(&FormatItem::ProgramName) {
+            object.string("program_name", process::program_name());
+        }
+
+        if self.contains(&FormatItem::Sequence) {
+            object.optional_number("sequence", record.sequence);
+        }
+
+        if let Some(precision) = self.find_precision(FormatItem::elapsed_precision) {
+            let elapsed = record.elapsed.map(|elapsed| precision.seconds(elapsed));
+            object.optional_number("elapsed", elapsed);
+        }
+
+        if let Some(precision) = self.find_precision(FormatItem::since_last_precision) {
+            let since_last = record
+                .since_last
+                .map(|since_last| precision.seconds(since_last));
+            object.optional_number("since_last", since_last);
+        }
+
+        if !record.fields.is_empty() {
+            let mut field_object = object.object("fields");
+
+            for (key, value) in record.fields.iter() {
+                field_object.field(key, value);
+            }
+
+            field_object.finish();
+        }
+
+        object.finish();
+    }
+
+    /// Get the precision of the first item `precision` returns one for.
+    fn find_precision(&self, precision: fn(&FormatItem) -> Option<Precision>) -> Option<Precision> {
+        return self
+            .items
+            .iter()
+            .find_map(|item| precision(item.unwrapped()));
+    }
+
+    /// Returns true if the format has `item`, either on its own or wrapped in a
+    /// [Layout](FormatItem::Layout).
+    fn contains(&self, item: &FormatItem) -> bool {
+        return self.items.iter().any(|i| i.unwrapped() == item);
+    }
+}
+
+/// Writes `value` to `writer` if there is one.
+fn write_optional<W: fmt::Write, D: Display>(writer: &mut W, value: Option<D>) -> fmt::Result {
+    return match value {
+        Some(value) => write!(writer, "{}", value),
+        None => Ok(()),
+    };
+}
+
+/// Forwards text written with [fmt::Write] to an [io::Write], keeping the error that stopped it.
+struct IoWriter<'a, W: io::Write> {
+    inner: &'a mut W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> fmt::Write for IoWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        return self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+
+            fmt::Error
+        });
+    }
+}
+
+impl FormatItem {
+    /// Returns true for the items that produce escape codes rather than text.
+    pub fn is_styling(&self) -> bool {
+        return matches!(
+            self.unwrapped(),
+            FormatItem::LevelColour | FormatItem::Style(_) | FormatItem::Reset
+        );
+    }
+
+    /// Wrap the item in a [Layout], replacing the layout of an item that is already wrapped.
+    ///
+    /// # Example
+    /// ```
+    /// use chrono::Utc;
+    /// use muxide_logging::format::{Format, FormatItem};
+    /// use muxide_logging::layout::{Alignment, Layout};
+    /// use muxide_logging::log::LogLevel;
+    ///
+    /// let level = FormatItem::LogLevel.with_layout(Layout::new().set_min_width(7));
+    /// let right = Layout::new().set_min_width(7).set_alignment(Alignment::Right);
+    /// let fmt = Format::<Utc>::new_tz()
+    ///     .append(level.with_layout(right))
+    ///     .append(FormatItem::CustomString(": done".to_string()));
+    ///
+    /// assert_eq!(fmt.build_string(LogLevel::Error, ""), "  Error: done");
+    /// ```
+    pub fn with_layout(self, layout: Layout) -> FormatItem {
+        return match self {
+            FormatItem::Layout(item, _) => FormatItem::Layout(item, layout),
+            item => FormatItem::Layout(Box::new(item), layout),
+        };
+    }
+
+    /// Get the item wrapped by a [Layout](FormatItem::Layout), or the item itself otherwise.
+    pub fn unwrapped(&self) -> &FormatItem {
+        return match self {
+            FormatItem::Layout(item, _) => item.unwrapped(),
+            item => item,
+        };
+    }
+
+    /// Returns true for the items that keep their end rather than their start when truncated.
+    fn truncates_left(&self) -> bool {
+        return matches!(
+            self,
+            FormatItem::ModulePath
+                | FormatItem::ShortModulePath(_)
+                | FormatItem::File
+                | FormatItem::FileName
+        );
+    }
+
+    /// Get the precision of an [Elapsed](FormatItem::Elapsed) item.
+    fn elapsed_precision(&self) -> Option<Precision> {
+        return match self {
+            FormatItem::Elapsed(precision) => Some(*precision),
+            _ => None,
+        };
+    }
+
+    /// Get the precision of a [SinceLast](FormatItem::SinceLast) item.
+    fn since_last_precision(&self) -> Option<Precision> {
+        return match self {
+            FormatItem::SinceLast(precision) => Some(*precision),
+            _ => None,
+        };
+    }
+
+    /// Maps a placeholder name and optional argument from a pattern string onto an item.
+    fn from_placeholder(
+        name: String,
+        argument: Option<String>,
+        offset: usize,
+    ) -> Result<Self, FormatParseError> {
+        let item = match name.as_str() {
+            "line" => FormatItem::LineNumber,
+            "column" => FormatItem::ColumnNumber,
+            "module" => {
+                return match argument {
+                    Some(argument) => match argument.parse() {
+                        Ok(style) => Ok(FormatItem::ShortModulePath(style)),
+                        Err(_) => Err(FormatParseError::InvalidArgument {
+                            offset,
+                            name,
+                            argument,
+                        }),
+                    },
+                    None => Ok(FormatItem::ModulePath),
+                };
+            }
+            "file" => {
+                return match argument.as_deref() {
+                    Some("basename") => Ok(FormatItem::FileName),
+                    Some(_) => Err(FormatParseError::InvalidArgument {
+                        offset,
+                        name,
+                        argument: argument.unwrap_or_default(),
+                    }),
+                    None => Ok(FormatItem::File),
+                };
+            }
+            "level" => FormatItem::LogLevel,
+            "message" => FormatItem::LogString,
+            "fields" => FormatItem::Fields,
+            "level_colour" => FormatItem::LevelColour,
+            "reset" => FormatItem::Reset,
+            "thread_id" => FormatItem::ThreadId,
+            "thread_name" => FormatItem::ThreadName,
+            "process_id" => FormatItem::ProcessId,
+            "hostname" => FormatItem::Hostname,
+            "program_name" => FormatItem::ProgramName,
+            "sequence" => FormatItem::Sequence,
+            "text" => {
+                let text = match argument {
+                    Some(text) => text,
+                    None => return Err(FormatParseError::MissingArgument { offset, name }),
+                };
+                let mut chars = text.chars();
+
+                return match (chars.next(), chars.next()) {
+                    (Some(ch), None) => Ok(FormatItem::CustomCharacter(ch)),
+                    _ => Ok(FormatItem::CustomString(text)),
+                };
+            }
+            "elapsed" | "since_last" => {
+                let precision = match argument {
+                    Some(argument) => match argument.parse() {
+                        Ok(precision) => precision,
+                        Err(_) => {
+                            return Err(FormatParseError::InvalidArgument {
+                                offset,
+                                name,
+                                argument,
+                            })
+                        }
+                    },
+                    None => Precision::default(),
+                };
+
+                return match name.as_str() {
+                    "elapsed" => Ok(FormatItem::Elapsed(precision)),
+                    _ => Ok(FormatItem::SinceLast(precision)),
+                };
+            }
+            "style" => {
+                return match argument {
+                    Some(argument) => match argument.parse() {
+                        Ok(style) => Ok(FormatItem::Style(style)),
+                        Err(_) => Err(FormatParseError::InvalidArgument {
+                            offset,
+                            name,
+                            argument,
+                        }),
+                    },
+                    None => Err(FormatParseError::MissingArgument { offset, name }),
+                };
+            }
+            "field" => {
+                return match argument {
+                    Some(key) => Ok(FormatItem::Field(key)),
+                    None => Err(FormatParseError::MissingArgument { offset, name }),
+                };
+            }
+            "time" => {
+                return match argument {
+                    Some(fmt_string) => Ok(FormatItem::TimeString(fmt_string)),
+                    None => Err(FormatParseError::MissingArgument { offset, name }),
+                };
+            }
+            _ => return Err(FormatParseError::UnknownPlaceholder { offset, name }),
+        };
+
+        if argument.is_some() {
+            return Err(FormatParseError::UnexpectedArgument { offset, name });
+        }
+
+        return Ok(item);
+    }
+}
+
+/// Consumes the next character if it is `expected`.
+fn next_is(chars: &mut Peekable<CharIndices>, expected: char) -> bool {
+    return chars.next_if(|&(_, ch)| ch == expected).is_some();
+}
+
+/// The name, argument and layout of a placeholder.
+type Placeholder = (String, Option<String>, Option<String>);
+
+/// Reads the remainder of a placeholder after its opening brace, returning the name, the argument
+/// following a `:` and the layout following a `|` if there are any. Braces and `|` within the
+/// argument are escaped by doubling them. Within an argument only the last `|` starts a layout,
+/// and only if a valid layout follows it, otherwise it is part of the argument.
+fn parse_placeholder(
+    chars: &mut Peekable<CharIndices>,
+    start: usize,
+) -> Result<Placeholder, FormatParseError> {
+    let mut name = String::new();
+
+    loop {
+        match chars.next() {
+            Some((_, '}')) => return Ok((name, None, None)),
+            Some((_, ':')) => break,
+            Some((_, '|')) => {
+                let layout = parse_layout(chars, start)?;
+
+                return Ok((name, None, Some(layout)));
+            }
+            Some((_, ch)) => name.push(ch),
+            None => return Err(FormatParseError::UnterminatedPlaceholder { offset: start }),
+        }
+    }
+
+    let mut argument = String::new();
+    // The position in the argument of the last unescaped `|`.
+    let mut separator = None;
+
+    loop {
+        match chars.next() {
+            Some((_, '{')) if next_is(chars, '{') => argument.push('{'),
+            Some((_, '}')) if next_is(chars, '}') => argument.push('}'),
+            Some((_, '|')) if next_is(chars, '|') => argument.push('|'),
+            Some((_, '}')) => {
+                let layout = separator
+                    .filter(|&i| argument[i + 1..].parse::<Layout>().is_ok())
+                    .map(|i| {
+                        let layout = argument[i + 1..].to_string();
+                        argument.truncate(i);
+
+                        return layout;
+                    });
+
+                return Ok((name, Some(argument), layout));
+            }
+            Some((_, '|')) => {
+                separator = Some(argument.len());
+                argument.push('|');
+            }
+            Some((offset, '{')) => return Err(FormatParseError::UnmatchedBrace { offset }),
+            Some((_, ch)) => argument.push(ch),
+            None => return Err(FormatParseError::UnterminatedPlaceholder { offset: start }),
+        }
+    }
+}
+
+/// Reads the layout of a placeholder up to and including its closing brace.
+fn parse_layout(
+    chars: &mut Peekable<CharIndices>,
+    start: usize,
+) -> Result<String, FormatParseError> {
+    let mut layout = String::new();
+
+    loop {
+        match chars.next() {
+            Some((_, '}')) => return Ok(layout),
+            Some((_, ch)) => layout.push(ch),
+            None => return Err(FormatParseError::UnterminatedPlaceholder { offset: start }),
+        }
+    }
+}
+
+/// Writes text with any braces doubled so that it is read back literally by [Format::parse], also
+/// doubling any `|` if the text is the argument of a placeholder.
+fn write_escaped(f: &mut Formatter<'_>, text: &str, argument: bool) -> fmt::Result {
+    for ch in text.chars() {
+        match ch {
+            '{' => f.write_str("{{")?,
+            '}' => f.write_str("}}")?,
+            '|' if argument => f.write_str("||")?,
+            _ => write!(f, "{}", ch)?,
+        }
+    }
+
+    return Ok(());
+}
+
+impl FormatItem {
+    /// Writes the item as a placeholder in the pattern syntax, followed by `layout` if there is
+    /// one. Literal text is written as a `{text:...}` placeholder.
+    fn write_placeholder(&self, f: &mut Formatter<'_>, layout: Option<&Layout>) -> fmt::Result {
+        let (name, argument) = match self {
+            FormatItem::LineNumber => ("line", None),
+            FormatItem::ColumnNumber => ("column", None),
+            FormatItem::ModulePath => ("module", None),
+            FormatItem::ShortModulePath(style) => ("module", Some(style.to_string())),
+            FormatItem::File => ("file", None),
+            FormatItem::FileName => ("file", Some("basename".to_string())),
+            FormatItem::LogLevel => ("level", None),
+            FormatItem::LogString => ("message", None),
+            FormatItem::TimeString(fmt_string) => ("time", Some(fmt_string.clone())),
+            FormatItem::CustomCharacter(ch) => ("text", Some(ch.to_string())),
+            FormatItem::CustomString(s) => ("text", Some(s.clone())),
+            FormatItem::Field(key) => ("field", Some(key.clone())),
+            FormatItem::Fields => ("fields", None),
+            FormatItem::LevelColour => ("level_colour", None),
+            FormatItem::Style(style) => ("style", Some(style.to_string())),
+            FormatItem::Reset => ("reset", None),
+            FormatItem::ThreadId => ("thread_id", None),
+            FormatItem::ThreadName => ("thread_name", None),
+            FormatItem::ProcessId => ("process_id", None),
+            FormatItem::Hostname => ("hostname", None),
+            FormatItem::ProgramName => ("program_name", None),
+            FormatItem::Sequence => ("sequence", None),
+            FormatItem::Elapsed(precision) => ("elapsed", Some(precision.as_str().to_string())),
+            FormatItem::SinceLast(precision) => {
+                ("since_last", Some(precision.as_str().to_string()))
+            }
+            FormatItem::Layout(item, item_layout) => {
+                return item.write_placeholder(f, layout.or(Some(item_layout)));
+            }
+        };
+
+        write!(f, "{{{}", name)?;
+
+        if let Some(argument) = argument {
+            f.write_str(":")?;
+            write_escaped(f, &argument, true)?;
+        }
+
+        // A layout without any widths has no effect and has no textual form.
+        if let Some(layout) = layout.filter(|l| l.min_width().is_some() || l.max_width().is_some())
+        {
+            write!(f, "|{}", layout)?;
+        }
+
+        return f.write_str("}");
+    }
+}
+
+impl Display for FormatItem {
+    /// Writes the item in the pattern syntax understood by [Format::parse].
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return match self {
+            FormatItem::CustomCharacter(ch) => write_escaped(f, ch.encode_utf8(&mut [0; 4]), false),
+            FormatItem::CustomString(s) => write_escaped(f, s, false),
+            item => item.write_placeholder(f, None),
+        };
+    }
+}
+
+impl Display for FormatParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return match self {
+            FormatParseError::UnknownPlaceholder { offset, name } => {
+                write!(f, "unknown placeholder '{}' at byte {}", name, offset)
+            }
+            FormatParseError::MissingArgument { offset, name } => {
+                write!(
+                    f,
+                    "placeholder '{}' at byte {} requires an argument",
+                    name, offset
+                )
+            }
+            FormatParseError::UnexpectedArgument { offset, name } => {
+                write!(
+                    f,
+                    "placeholder '{}' at byte {} does not take an argument",
+                    name, offset
+                )
+            }
+            FormatParseError::UnterminatedPlaceholder { offset } => {
+                write!(f, "unterminated placeholder starting at byte {}", offset)
+            }
+            FormatParseError::UnmatchedBrace { offset } => {
+                write!(f, "unmatched brace at byte {}", offset)
+            }
+            FormatParseError::InvalidArgument {
+                offset,
+                name,
+                argument,
+            } => {
+                write!(
+                    f,
+                    "invalid argument '{}' for placeholder '{}' at byte {}",
+                    argument, name, offset
+                )
+            }
+            FormatParseError::InvalidLayout { offset, layout } => {
+                write!(f, "invalid layout '{}' at byte {}", layout, offset)
+            }
+        };
+    }
+}
+
+impl std::error::Error for FormatParseError {}
+
+impl Precision {
+    /// The name of the precision used in pattern strings, such as `ms`.
+    pub const fn as_str(&self) -> &'static str {
+        return match self {
+            Precision::Seconds => "s",
+            Precision::Milliseconds => "ms",
+            Precision::Microseconds => "us",
+            Precision::Nanoseconds => "ns",
+        };
+    }
+
+    /// Write `duration` as a number of seconds with this precision, truncating any smaller units.
+    pub fn format(&self, duration: Duration) -> String {
+        return self.seconds(duration).to_string();
+    }
+
+    /// Get `duration` as a number of seconds with this precision that can be displayed.
+    fn seconds(&self, duration: Duration) -> Seconds {
+        return Seconds {
+            precision: *self,
+            duration,
+        };
+    }
+}
+
+impl<Tz: TimeZone> Display for Rfc3339<Tz> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let time = self.0.naive_local();
+        // Leap seconds are represented by a nanosecond value of at least one second.
+        let (second, nanos) = match time.nanosecond() {
+            nanos if nanos >= 1_000_000_000 => (time.second() + 1, nanos - 1_000_000_000),
+            nanos => (time.second(), nanos),
+        };
+
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            time.year(),
+            time.month(),
+            time.day(),
+            time.hour(),
+            time.minute(),
+            second
+        )?;
+
+        match nanos {
+            0 => {}
+            nanos if nanos % 1_000_000 == 0 => write!(f, ".{:03}", nanos / 1_000_000)?,
+            nanos if nanos % 1_000 == 0 => write!(f, ".{:06}", nanos / 1_000)?,
+            nanos => write!(f, ".{:09}", nanos)?,
+        }
+
+        let offset = self.0.offset().fix().local_minus_utc();
+        let sign = if offset < 0 { '-' } else { '+' };
+        let offset = offset.abs();
+
+        return write!(f, "{}{:02}:{:02}", sign, offset / 3600, offset / 60 % 60);
+    }
+}
+
+impl Display for Seconds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let sign = if self.duration < Duration::zero() {
+            "-"
+        } else {
+            ""
+        };
+        let duration = if self.duration < Duration::zero() {
+            -self.duration
+        } else {
+            self.duration
+        };
+        let seconds = duration.num_seconds();
+        let nanos = (duration - Duration::seconds(seconds))
+            .num_nanoseconds()
+            .unwrap_or(0);
+
+        return match self.precision {
+            Precision::Seconds => write!(f, "{}{}", sign, seconds),
+            Precision::Milliseconds => write!(f, "{}{}.{:03}", sign, seconds, nanos / 1_000_000),
+            Precision::Microseconds => write!(f, "{}{}.{:06}", sign, seconds, nanos / 1_000),
+            Precision::Nanoseconds => write!(f, "{}{}.{:09}", sign, seconds, nanos),
+        };
+    }
+}
+
+impl ModulePathStyle {
+    /// Shorten the module `path` with this style.
+    pub fn shorten(&self, path: &str) -> String {
+        let mut shortened = String::with_capacity(path.len());
+        let _ = self.write(&mut shortened, path);
+
+        return shortened;
+    }
+
+    /// Write the module `path` shortened with this style to `writer`.
+    fn write<W: fmt::Write>(&self, writer: &mut W, path: &str) -> fmt::Result {
+        return match self {
+            ModulePathStyle::Abbreviated => {
+                let (parents, last) = match path.rsplit_once("::") {
+                    Some(split) => split,
+                    None => return writer.write_str(path),
+                };
+
+                for segment in parents.split("::") {
+                    if let Some(ch) = segment.chars().next() {
+                        writer.write_char(ch)?;
+                    }
+
+                    writer.write_str("::")?;
+                }
+
+                writer.write_str(last)
+            }
+            ModulePathStyle::Last(0) => Ok(()),
+            ModulePathStyle::Last(count) => {
+                let start = match path.rmatch_indices("::").nth(count - 1) {
+                    Some((idx, _)) => idx + 2,
+                    None => 0,
+                };
+
+                writer.write_str(&path[start..])
+            }
+            ModulePathStyle::StripPrefix(prefix) => {
+                let stripped = path
+                    .strip_prefix(prefix.as_str())
+                    .and_then(|rest| rest.strip_prefix("::"));
+
+                writer.write_str(stripped.unwrap_or(path))
+            }
+        };
+    }
+}
+
+/// Get the last component of a file path, accepting either `/` or `\` as the separator since
+/// `file!()` uses the separator of the platform the crate was built on.
+fn file_name(file: &str) -> &str {
+    return file.rsplit(['/', '\\']).next().unwrap_or(file);
+}
+
+impl Display for ModulePathStyle {
+    /// Writes the style as it is written in pattern strings, such as `last=2`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return match self {
+            ModulePathStyle::Abbreviated => f.write_str("abbreviated"),
+            ModulePathStyle::Last(count) => write!(f, "last={}", count),
+            ModulePathStyle::StripPrefix(prefix) => write!(f, "strip={}", prefix),
+        };
+    }
+}
+
+impl FromStr for ModulePathStyle {
+    type Err = ();
+
+    /// Parses `abbreviated`, `last=<n>` or `strip=<prefix>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.split_once('=') {
+            None if s == "abbreviated" => Ok(ModulePathStyle::Abbreviated),
+            Some(("last", count)) => count.parse().map(ModulePathStyle::Last).map_err(|_| ()),
+            Some(("strip", prefix)) if !prefix.is_empty() => {
+                Ok(ModulePathStyle::StripPrefix(prefix.to_string()))
+            }
+            _ => Err(()),
+        };
+    }
+}
+
+impl FromStr for Precision {
+    type Err = ();
+
+    /// Parses a precision from its [name](Precision::as_str).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "s" => Ok(Precision::Seconds),
+            "ms" => Ok(Precision::Milliseconds),
+            "us" => Ok(Precision::Microseconds),
+            "ns" => Ok(Precision::Nanoseconds),
+            _ => Err(()),
+        };
+    }
+}
+
+impl<'a, Tz: TimeZone> Record<'a, Tz> {
+    /// Create a record without a sequence number or times, as for a format built without a
+    /// [LogItem](crate::log::LogItem).
+    pub(crate) fn new(
+        timestamp: DateTime<Tz>,
+        level: LogLevel,
+        message: &'a str,
+        fields: &'a Fields,
+    ) -> Self {
+        return Self {
+            timestamp,
+            level,
+            message,
+            fields,
+            sequence: None,
+            elapsed: None,
+            since_last: None,
+        };
+    }
+}
+
+impl FormatParseError {
+    /// The byte offset into the pattern string where the error occurred.
+    pub fn offset(&self) -> usize {
+        return match self {
+            FormatParseError::UnknownPlaceholder { offset, .. }
+            | FormatParseError::MissingArgument { offset, .. }
+            | FormatParseError::UnexpectedArgument { offset, .. }
+            | FormatParseError::UnterminatedPlaceholder { offset }
+            | FormatParseError::UnmatchedBrace { offset }
+            | FormatParseError::InvalidArgument { offset, .. }
+            | FormatParseError::InvalidLayout { offset, .. } => *offset,
+        };
+    }
+}
+
+impl Origin {
+    fn new(text: Text) -> Self {
+        return Self {
+            text: Some(text),
+            owned: OnceLock::new(),
+        };
+    }
+
+    fn is_none(&self) -> bool {
+        return self.text.is_none();
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        return self.text.as_ref().map(|text| {
+            return match text {
+                Text::Owned(text) => text.as_str(),
+                Text::Static(text) => text,
+                Text::ThreadName(thread) => thread.name().unwrap_or_default(),
+            };
+        });
+    }
+
+    fn as_string(&self) -> &Option<String> {
+        return self.owned.get_or_init(|| self.as_str().map(str::to_string));
+    }
+}
+
+impl PartialEq for Origin {
+    fn eq(&self, other: &Self) -> bool {
+        return self.as_str() == other.as_str();
+    }
+}
+
+impl<Tz: TimeZone> Display for Format<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    /// Writes the sequence of items as a pattern string, see [Format::parse].
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for item in self.items.iter() {
+            write!(f, "{}", item)?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl<Tz: DefaultTimeZone> FromStr for Format<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    type Err = FormatParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return Self::parse(s);
+    }
+}
+
+impl<Tz: TimeZone> Index<usize> for Format<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    type Output = FormatItem;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        return self.items.index(index);
+    }
+}
+
+impl<Tz: TimeZone> IndexMut<usize> for Format<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        return Arc::make_mut(&mut self.items).index_mut(index);
+    }
+}
+
+impl<Tz: TimeZone> PartialEq for Format<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        return self.file == other.file
+            && self.custom_time == other.custom_time
+            && self.module_path == other.module_path
+            && self.thread_id == other.thread_id
+            && self.thread_name == other.thread_name
+            && self.column == other.column
+            && self.line == other.line
+            && self.items == other.items
+            && self.palette == other.palette
+            && self.colour_mode == other.colour_mode;
+    }
+}
+
+impl Default for Format<Local> {
+    /// Creates a new instance of [Format] with the format
+    /// `[HH:MM:SS] (module_path line:column) log_level: log_message`
+    fn default() -> Self {
+        return Self::default_in(Local);
+    }
+}
+
+impl From<Format<Local>> for Format<Utc> {
+    fn from(fmt: Format<Local>) -> Self {
+        return Self {
+            items: fmt.items,
+            column: fmt.column,
+            line: fmt.line,
+            file: fmt.file,
+            module_path: fmt.module_path,
+            thread_id: fmt.thread_id,
+            thread_name: fmt.thread_name,
+            custom_time: fmt.custom_time.map(|dt| dt.into()),
+            timezone: Utc,
+            palette: fmt.palette,
+            colour_mode: fmt.colour_mode,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::format::{
+        Format, FormatItem, FormatParseError, ModulePathStyle, Origin, Precision, Rfc3339,
+    };
+    use crate::layout::{Alignment, Layout};
+    use crate::log::{Fields, LogLevel};
+    use crate::style::{Colour, ColourMode, Palette, Style};
+    use chrono::{DateTime, Duration, FixedOffset, Local, TimeZone, Utc};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_default() {
+        assert_eq!(
+            Format::default(),
+            Format {
+                items: Arc::new(vec![
+                    FormatItem::CustomCharacter('['),
+                    FormatItem::TimeString("%k:%M:%S".to_string()),
+                    FormatItem::CustomString("] (".to_string()),
+                    FormatItem::ModulePath,
+                    FormatItem::CustomCharacter(' '),
+                    FormatItem::LineNumber,
+                    FormatItem::CustomCharacter(':'),
+                    FormatItem::ColumnNumber,
+                    FormatItem::CustomString(") ".to_string()),
+                    FormatItem::LogLevel,
+                    FormatItem::CustomString(": ".to_string()),
+                    FormatItem::LogString
+                ]),
+                column: None,
+                line: None,
+                file: Origin::default(),
+                module_path: Origin::default(),
+                thread_id: None,
+                thread_name: Origin::default(),
+                custom_time: None,
+                timezone: Local,
+                palette: None,
+                colour_mode: ColourMode::Auto
+            }
+        )
+    }
+
+    #[test]
     fn test_build_default() {
         // Use Utc for these tests so they pass on other machines
         assert_eq!(
@@ -438,4 +1970,380 @@ mod tests {
             FormatItem::LogLevel
         );
     }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            Format::<Local>::parse("{{{level}}} {time:%H:%M:%S} {file}{message}").unwrap(),
+            Format::new()
+                .append(FormatItem::CustomCharacter('{'))
+                .append(FormatItem::LogLevel)
+                .append(FormatItem::CustomString("} ".to_string()))
+                .append(FormatItem::TimeString("%H:%M:%S".to_string()))
+                .append(FormatItem::CustomCharacter(' '))
+                .append(FormatItem::File)
+                .append(FormatItem::LogString)
+        );
+    }
+
+    #[test]
+    fn test_parse_argument_pipe() {
+        let time = |pattern: &str| FormatItem::TimeString(pattern.to_string());
+
+        assert_eq!(
+            Format::<Utc>::parse("{time:%H|%M}").unwrap()[0],
+            time("%H|%M")
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{time:%H|%M|x}").unwrap()[0],
+            time("%H|%M|x")
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{time:%H|%M|>9}").unwrap()[0],
+            time("%H|%M").with_layout(
+                Layout::new()
+                    .set_min_width(9)
+                    .set_alignment(Alignment::Right)
+            )
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{time:%H||5}").unwrap()[0],
+            time("%H|5")
+        );
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let patterns = [
+            "[{time:%k:%M:%S}] ({module} {line}:{column}) {level}: {message}",
+            "{{literal}} {time:{{%Y}}} x",
+            "{thread_name} ({thread_id})",
+            "{hostname} {program_name}[{process_id}]",
+            "#{sequence} +{elapsed:s} +{since_last:ns}",
+            "{level|7} {module|>20.20} {time:%H||%M|^9} {text:->|.4}",
+            "{module:abbreviated} {module:last=2|.12} {file:basename}",
+            "{module:strip=muxide::server}",
+            "",
+        ];
+
+        for pattern in patterns.iter() {
+            let fmt = Format::<Utc>::parse(pattern).unwrap();
+
+            assert_eq!(&fmt.to_string(), pattern);
+            assert_eq!(Format::<Utc>::parse(&fmt.to_string()).unwrap(), fmt);
+        }
+
+        assert_eq!(
+            Format::<Local>::parse(&Format::default().to_string()).unwrap(),
+            Format::default()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Format::<Utc>::parse("ab {lvl}").unwrap_err(),
+            FormatParseError::UnknownPlaceholder {
+                offset: 3,
+                name: "lvl".to_string()
+            }
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{time}").unwrap_err(),
+            FormatParseError::MissingArgument {
+                offset: 0,
+                name: "time".to_string()
+            }
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{line:3}").unwrap_err(),
+            FormatParseError::UnexpectedArgument {
+                offset: 0,
+                name: "line".to_string()
+            }
+        );
+        assert_eq!(
+            Format::<Utc>::parse("x {message").unwrap_err(),
+            FormatParseError::UnterminatedPlaceholder { offset: 2 }
+        );
+        assert_eq!(
+            Format::<Utc>::parse("a}b").unwrap_err(),
+            FormatParseError::UnmatchedBrace { offset: 1 }
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{level} {module|>x}").unwrap_err(),
+            FormatParseError::InvalidLayout {
+                offset: 8,
+                layout: ">x".to_string()
+            }
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{level|7").unwrap_err(),
+            FormatParseError::UnterminatedPlaceholder { offset: 0 }
+        );
+    }
+
+    #[test]
+    fn test_build_layout() {
+        let fmt = Format::<Utc>::parse("[{level|^7}] {module|>16.16} {file|.10} {message|.8}")
+            .unwrap()
+            .set_module_path("muxide::server::pty::reader")
+            .set_file("src/server/pty/reader.rs");
+
+        assert_eq!(
+            fmt.build_string(LogLevel::Error, "Hello, World!"),
+            "[ Error ] …er::pty::reader …reader.rs Hello, …"
+        );
+
+        let item = FormatItem::LogLevel
+            .with_layout(Layout::new().set_min_width(3))
+            .with_layout(Layout::new().set_min_width(9));
+
+        assert_eq!(
+            item,
+            FormatItem::Layout(
+                Box::new(FormatItem::LogLevel),
+                Layout::new().set_min_width(9)
+            )
+        );
+        assert_eq!(item.unwrapped(), &FormatItem::LogLevel);
+    }
+
+    #[test]
+    fn test_build_fields() {
+        let fields: Fields = vec![("pane", 2i64), ("cols", 80)].into_iter().collect();
+
+        assert_eq!(
+            Format::new()
+                .append(FormatItem::Field("cols".to_string()))
+                .append(FormatItem::CustomCharacter(' '))
+                .append(FormatItem::Field("rows".to_string()))
+                .append(FormatItem::CustomCharacter('|'))
+                .append(FormatItem::Fields)
+                .build_string_with_fields(LogLevel::Information, "", &fields),
+            "80 |pane=2"
+        );
+    }
+
+    #[test]
+    fn test_build_styles() {
+        let fmt = Format::<Utc>::parse("{level_colour}{level}{reset} {style:bold}{message}{reset}")
+            .unwrap()
+            .set_palette(Palette::default().set(LogLevel::Warning, Colour::BrightYellow));
+
+        assert_eq!(fmt[4], FormatItem::Style(Style::Bold));
+        assert_eq!(
+            fmt.clone()
+                .set_colour_mode(ColourMode::Always)
+                .build_string(LogLevel::Warning, "Msg"),
+            "\x1b[93mWarning\x1b[0m \x1b[1mMsg\x1b[0m"
+        );
+        assert_eq!(
+            fmt.set_colour_mode(ColourMode::Never)
+                .build_string(LogLevel::Warning, "Msg"),
+            "Warning Msg"
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{style:loud}").unwrap_err(),
+            FormatParseError::InvalidArgument {
+                offset: 0,
+                name: "style".to_string(),
+                argument: "loud".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_thread() {
+        let spawn = |name: Option<&str>| {
+            let mut builder = std::thread::Builder::new();
+
+            if let Some(name) = name {
+                builder = builder.name(name.to_string());
+            }
+
+            return builder
+                .spawn(|| {
+                    Format::<Utc>::parse("{thread_name} {thread_id}")
+                        .unwrap()
+                        .set_current_thread()
+                })
+                .unwrap()
+                .join()
+                .unwrap();
+        };
+
+        // The thread is captured when the format is created, not when it is built.
+        let fmt = spawn(Some("worker"));
+        let id = fmt.thread_id().unwrap();
+        assert_ne!(id, thread_id::get());
+        assert_eq!(fmt.thread_name(), &Some("worker".to_string()));
+        assert_eq!(fmt.thread_name_str(), Some("worker"));
+        assert_eq!(
+            fmt.build_string(LogLevel::Information, ""),
+            format!("worker {}", id)
+        );
+
+        let fmt = spawn(None);
+        let id = fmt.thread_id().unwrap();
+        assert_eq!(fmt.thread_name(), &None);
+        assert_eq!(
+            fmt.build_string(LogLevel::Information, ""),
+            format!("{} {}", id, id)
+        );
+    }
+
+    #[test]
+    fn test_static_origin() {
+        let fmt = Format::new_in(Utc)
+            .set_static_file("src/main.rs")
+            .set_static_module_path("app::main");
+
+        assert_eq!(fmt.file(), &Some("src/main.rs".to_string()));
+        assert_eq!(fmt.file_str(), Some("src/main.rs"));
+        assert_eq!(fmt.module_path(), &Some("app::main".to_string()));
+        assert_eq!(fmt.module_path_str(), Some("app::main"));
+        assert_eq!(
+            fmt,
+            Format::new_in(Utc)
+                .set_file("src/main.rs")
+                .set_module_path("app::main")
+        );
+    }
+
+    #[test]
+    fn test_build_process() {
+        let hostname = gethostname::gethostname().to_string_lossy().into_owned();
+        let exe = std::env::current_exe().unwrap();
+        let program_name = exe.file_stem().unwrap().to_string_lossy();
+        let fmt = Format::<Utc>::parse("{hostname} {program_name}[{process_id}]")
+            .unwrap()
+            .set_constant_time(DateTime::from(
+                DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
+            ));
+
+        assert_eq!(
+            fmt.clone().build_string(LogLevel::Information, ""),
+            format!("{} {}[{}]", hostname, program_name, std::process::id())
+        );
+        assert_eq!(
+            fmt.build_json(LogLevel::Information, "", &Fields::new()),
+            format!(
+                r#"{{"timestamp":"2003-07-01T10:52:37+00:00","level":"Information","message":"","module_path":null,"file":null,"line":null,"column":null,"process_id":{},"hostname":"{}","program_name":"{}"}}"#,
+                std::process::id(),
+                hostname,
+                program_name
+            )
+        );
+    }
+
+    #[test]
+    fn test_module_path_style() {
+        let path = "muxide::server::session::pane";
+
+        assert_eq!(ModulePathStyle::Abbreviated.shorten(path), "m::s::s::pane");
+        assert_eq!(ModulePathStyle::Abbreviated.shorten("muxide"), "muxide");
+        assert_eq!(ModulePathStyle::Last(2).shorten(path), "session::pane");
+        assert_eq!(ModulePathStyle::Last(9).shorten(path), path);
+        assert_eq!(
+            ModulePathStyle::StripPrefix("muxide::server".to_string()).shorten(path),
+            "session::pane"
+        );
+        // Only whole segments are stripped.
+        assert_eq!(
+            ModulePathStyle::StripPrefix("mux".to_string()).shorten(path),
+            path
+        );
+
+        let fmt = Format::<Utc>::parse("{module:abbreviated} {file:basename}")
+            .unwrap()
+            .set_module_path(path)
+            .set_file("src/server/session/pane.rs");
+
+        assert_eq!(
+            fmt.build_string(LogLevel::Information, ""),
+            "m::s::s::pane pane.rs"
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{module:last=x}").unwrap_err(),
+            FormatParseError::InvalidArgument {
+                offset: 0,
+                name: "module".to_string(),
+                argument: "last=x".to_string()
+            }
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{file:dir}").unwrap_err(),
+            FormatParseError::InvalidArgument {
+                offset: 0,
+                name: "file".to_string(),
+                argument: "dir".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_rfc3339() {
+        let offset = FixedOffset::west(9 * 3600 + 30 * 60);
+        let times = [
+            Utc.ymd(2003, 7, 1)
+                .and_hms(10, 52, 37)
+                .with_timezone(&offset),
+            Utc.ymd(2003, 7, 1)
+                .and_hms_milli(10, 52, 37, 120)
+                .with_timezone(&offset),
+            Utc.ymd(1, 1, 1)
+                .and_hms_micro(0, 0, 0, 5)
+                .with_timezone(&offset),
+            Utc.ymd(2016, 12, 31)
+                .and_hms_nano(23, 59, 59, 1_000_000_001)
+                .with_timezone(&offset),
+            Utc.ymd(2021, 6, 15)
+                .and_hms_nano(8, 0, 0, 123_456_789)
+                .with_timezone(&offset),
+        ];
+
+        for time in times.iter() {
+            assert_eq!(Rfc3339(*time).to_string(), time.to_rfc3339());
+            assert_eq!(
+                Rfc3339(time.with_timezone(&Utc)).to_string(),
+                time.with_timezone(&Utc).to_rfc3339()
+            );
+        }
+    }
+
+    #[test]
+    fn test_precision() {
+        let duration = Duration::milliseconds(12_345) + Duration::nanoseconds(678_901);
+
+        assert_eq!(Precision::Seconds.format(duration), "12");
+        assert_eq!(Precision::Milliseconds.format(duration), "12.345");
+        assert_eq!(Precision::Microseconds.format(duration), "12.345678");
+        assert_eq!(Precision::Nanoseconds.format(duration), "12.345678901");
+        assert_eq!(Precision::Milliseconds.format(-duration), "-12.345");
+        assert_eq!(Precision::Milliseconds.format(Duration::zero()), "0.000");
+
+        assert_eq!(
+            Format::<Utc>::parse("{elapsed}{since_last:us}").unwrap(),
+            Format::<Utc>::new_tz()
+                .append(FormatItem::Elapsed(Precision::Milliseconds))
+                .append(FormatItem::SinceLast(Precision::Microseconds))
+        );
+        assert_eq!(
+            Format::<Utc>::parse("{elapsed:min}").unwrap_err(),
+            FormatParseError::InvalidArgument {
+                offset: 0,
+                name: "elapsed".to_string(),
+                argument: "min".to_string()
+            }
+        );
+
+        // Without a log there is no sequence number or times to write.
+        assert_eq!(
+            Format::<Utc>::parse("[{sequence}{elapsed}{since_last}]")
+                .unwrap()
+                .build_string(LogLevel::Information, ""),
+            "[]"
+        );
+    }
 }
@@ -0,0 +1,195 @@
+//! A minimal JSON writer used by the [Json](crate::format::Encoding::Json) encoding.
+
+use crate::log::FieldValue;
+use std::fmt::{self, Display, Write};
+
+/// Builds a single JSON object on one line at the end of a buffer. Keys are written in the order
+/// they are added.
+pub(crate) struct JsonObject<'a> {
+    buffer: &'a mut String,
+    empty: bool,
+}
+
+/// Escapes any text written to it into a buffer, see [escape_into].
+struct Escaper<'a>(&'a mut String);
+
+impl<'a> JsonObject<'a> {
+    /// Start a new, empty object at the end of `buffer`.
+    pub(crate) fn new(buffer: &'a mut String) -> Self {
+        buffer.push('{');
+
+        return Self {
+            buffer,
+            empty: true,
+        };
+    }
+
+    /// Write the key and the separating colon, preceded by a comma if it isn't the first key.
+    fn key(&mut self, key: &str) {
+        if !self.empty {
+            self.buffer.push(',');
+        }
+
+        self.empty = false;
+        escape_into(self.buffer, key);
+        self.buffer.push(':');
+    }
+
+    /// Add a string value.
+    pub(crate) fn string(&mut self, key: &str, value: &str) {
+        self.key(key);
+        escape_into(self.buffer, value);
+    }
+
+    /// Add a string value written with its [Display] implementation.
+    pub(crate) fn display<D: Display>(&mut self, key: &str, value: D) {
+        self.key(key);
+        self.buffer.push('"');
+        // Writing to a String never fails.
+        let _ = write!(Escaper(self.buffer), "{}", value);
+        self.buffer.push('"');
+    }
+
+    /// Add a string value or `null`.
+    pub(crate) fn optional_string(&mut self, key: &str, value: Option<&str>) {
+        match value {
+            Some(value) => self.string(key, value),
+            None => self.raw(key, "null"),
+        }
+    }
+
+    /// Add a numeric value.
+    pub(crate) fn number<N: Display>(&mut self, key: &str, value: N) {
+        self.key(key);
+        let _ = write!(self.buffer, "{}", value);
+    }
+
+    /// Add a numeric value or `null`.
+    pub(crate) fn optional_number<N: Display>(&mut self, key: &str, value: Option<N>) {
+        match value {
+            Some(value) => self.number(key, value),
+            None => self.raw(key, "null"),
+        }
+    }
+
+    /// Add a field value using the matching JSON type. Non-finite floats are written as `null`
+    /// as JSON has no representation for them.
+    pub(crate) fn field(&mut self, key: &str, value: &FieldValue) {
+        match value {
+            FieldValue::String(s) => self.string(key, s),
+            FieldValue::Integer(i) => self.number(key, i),
+            FieldValue::Float(x) if x.is_finite() => {
+                self.key(key);
+                let _ = write!(self.buffer, "{:?}", x);
+            }
+            FieldValue::Float(_) => self.raw(key, "null"),
+            FieldValue::Bool(b) => self.number(key, b),
+        }
+    }
+
+    /// Start a nested object, which must be [finished](JsonObject::finish) before adding any
+    /// more keys to this object.
+    pub(crate) fn object(&mut self, key: &str) -> JsonObject<'_> {
+        self.key(key);
+
+        return JsonObject::new(self.buffer);
+    }
+
+    /// Add a value that is already valid JSON.
+    fn raw(&mut self, key: &str, value: &str) {
+        self.key(key);
+        self.buffer.push_str(value);
+    }
+
+    /// Close the object.
+    pub(crate) fn finish(self) {
+        self.buffer.push('}');
+    }
+}
+
+/// Append `value` as a quoted JSON string. Control characters are escaped and any non-ASCII
+/// character is written as a `\u` escape, using surrogate pairs where required, so the output is
+/// plain ASCII and safe for consumers that do not handle UTF-8.
+pub(crate) fn escape_into(buffer: &mut String, value: &str) {
+    buffer.push('"');
+    let _ = Escaper(buffer).write_str(value);
+    buffer.push('"');
+}
+
+impl Write for Escaper<'_> {
+    fn write_str(&mut self, value: &str) -> fmt::Result {
+        let buffer = &mut *self.0;
+
+        for ch in value.chars() {
+            match ch {
+                '"' => buffer.push_str("\\\""),
+                '\\' => buffer.push_str("\\\\"),
+                '\n' => buffer.push_str("\\n"),
+                '\r' => buffer.push_str("\\r"),
+                '\t' => buffer.push_str("\\t"),
+                '\u{08}' => buffer.push_str("\\b"),
+                '\u{0C}' => buffer.push_str("\\f"),
+                ' '..='~' => buffer.push(ch),
+                _ => {
+                    let mut units = [0u16; 2];
+
+                    for unit in ch.encode_utf16(&mut units) {
+                        write!(buffer, "\\u{:04x}", unit)?;
+                    }
+                }
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::json::{escape_into, JsonObject};
+    use crate::log::FieldValue;
+
+    fn escaped(value: &str) -> String {
+        let mut buffer = String::new();
+        escape_into(&mut buffer, value);
+
+        return buffer;
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escaped("plain"), "\"plain\"");
+        assert_eq!(escaped("a\"b\\c"), "\"a\\\"b\\\\c\"");
+        assert_eq!(escaped("line\nbreak\t\u{0}"), "\"line\\nbreak\\t\\u0000\"");
+        assert_eq!(escaped("\u{7f}é\u{2028}"), "\"\\u007f\\u00e9\\u2028\"");
+        assert_eq!(escaped("😀"), "\"\\ud83d\\ude00\"");
+    }
+
+    #[test]
+    fn test_object() {
+        let mut buffer = String::new();
+        let mut object = JsonObject::new(&mut buffer);
+        object.string("a", "b");
+        object.optional_number("n", Some(3));
+        object.optional_string("s", None);
+
+        let mut inner = object.object("fields");
+        inner.field("ratio", &FieldValue::Float(1.0));
+        inner.field("nan", &FieldValue::Float(f64::NAN));
+        inner.field("ok", &FieldValue::Bool(true));
+        inner.finish();
+        object.finish();
+
+        assert_eq!(
+            buffer,
+            "{\"a\":\"b\",\"n\":3,\"s\":null,\"fields\":{\"ratio\":1.0,\"nan\":null,\"ok\":true}}"
+        );
+
+        let mut buffer = String::new();
+        let mut object = JsonObject::new(&mut buffer);
+        object.display("d", format_args!("{}\"{}", 'é', 1));
+        object.finish();
+
+        assert_eq!(buffer, "{\"d\":\"\\u00e9\\\"1\"}");
+    }
+}
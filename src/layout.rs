@@ -0,0 +1,367 @@
+//! Width, alignment and truncation of [FormatItem](crate::format::FormatItem)s, so that variable
+//! length items such as the module path and log level line up between logs.
+//!
+//! Widths are measured in terminal columns using the display width of each character, so wide
+//! characters such as CJK ideographs count as two columns.
+//!
+//! # Example
+//! ```
+//! use chrono::Utc;
+//! use muxide_logging::format::{Format, FormatItem};
+//! use muxide_logging::layout::{Alignment, Layout};
+//! use muxide_logging::log::LogLevel;
+//!
+//! let fmt = Format::<Utc>::new_tz()
+//!     .append(FormatItem::LogLevel.with_layout(Layout::new().set_min_width(7)))
+//!     .append(FormatItem::CustomCharacter('|'))
+//!     .append(FormatItem::ModulePath.with_layout(
+//!         Layout::new().set_min_width(14).set_max_width(14).set_alignment(Alignment::Right),
+//!     ))
+//!     .set_module_path("muxide::server::pty::reader");
+//!
+//! assert_eq!(fmt.to_string(), "{level|7}|{module|>14.14}");
+//! assert_eq!(fmt.build_string(LogLevel::Error, ""), "Error  |…::pty::reader");
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use unicode_width::UnicodeWidthChar;
+use unicode_width::UnicodeWidthStr;
+
+/// The character marking where text was truncated.
+pub const ELLIPSIS: char = '…';
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+/// Where text narrower than the minimum width of a [Layout] is placed.
+pub enum Alignment {
+    /// Pad on the right, written as `<`.
+    #[default]
+    Left,
+    /// Pad on the left, written as `>`.
+    Right,
+    /// Pad evenly on both sides, with any odd column on the right, written as `^`.
+    Centre,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+/// The width and alignment of a wrapped [FormatItem](crate::format::FormatItem), see
+/// [FormatItem::with_layout](crate::format::FormatItem::with_layout).
+///
+/// Text narrower than the minimum width is padded with spaces according to the [Alignment]. Text
+/// wider than the maximum width is truncated and ends with an [ELLIPSIS], module paths and files
+/// are truncated from the left so that the most specific part is kept.
+///
+/// In pattern strings a layout follows the placeholder after a `|` as
+/// `[alignment][min_width][.max_width]`, for example `{level|<7}` or `{module|>20.20}`.
+pub struct Layout {
+    min_width: Option<usize>,
+    max_width: Option<usize>,
+    alignment: Alignment,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// The error returned when parsing an invalid [Layout].
+pub struct ParseLayoutError {
+    layout: String,
+}
+
+impl Alignment {
+    /// The character used for the alignment in pattern strings.
+    pub const fn as_char(&self) -> char {
+        return match self {
+            Alignment::Left => '<',
+            Alignment::Right => '>',
+            Alignment::Centre => '^',
+        };
+    }
+}
+
+impl Layout {
+    /// Create a new [Layout] without a minimum or maximum width.
+    pub const fn new() -> Self {
+        return Self {
+            min_width: None,
+            max_width: None,
+            alignment: Alignment::Left,
+        };
+    }
+
+    /// Pad text narrower than `width` columns.
+    pub const fn set_min_width(mut self, width: usize) -> Self {
+        self.min_width = Some(width);
+
+        return self;
+    }
+
+    /// Truncate text wider than `width` columns.
+    pub const fn set_max_width(mut self, width: usize) -> Self {
+        self.max_width = Some(width);
+
+        return self;
+    }
+
+    /// Set where padded text is placed.
+    pub const fn set_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+
+        return self;
+    }
+
+    /// Get the minimum width, if any.
+    pub const fn min_width(&self) -> Option<usize> {
+        return self.min_width;
+    }
+
+    /// Get the maximum width, if any.
+    pub const fn max_width(&self) -> Option<usize> {
+        return self.max_width;
+    }
+
+    /// Get where padded text is placed.
+    pub const fn alignment(&self) -> Alignment {
+        return self.alignment;
+    }
+
+    /// Write `text` to `writer` truncated and padded to fit the layout, truncating from the left
+    /// if `truncate_left` is true.
+    pub(crate) fn write<W: fmt::Write>(
+        &self,
+        writer: &mut W,
+        text: &str,
+        truncate_left: bool,
+    ) -> fmt::Result {
+        let mut text = text;
+        let mut width = text.width();
+        let mut ellipsis = false;
+
+        if let Some(max_width) = self.max_width {
+            if width > max_width {
+                let ellipsis_width = ELLIPSIS.width().unwrap_or(1);
+
+                ellipsis = max_width >= ellipsis_width;
+                text = truncate(
+                    text,
+                    max_width.saturating_sub(ellipsis_width),
+                    truncate_left,
+                );
+                width = text.width() + if ellipsis { ellipsis_width } else { 0 };
+            }
+        }
+
+        let padding = match self.min_width {
+            Some(min_width) => min_width.saturating_sub(width),
+            None => 0,
+        };
+
+        let (left, right) = match self.alignment {
+            Alignment::Left => (0, padding),
+            Alignment::Right => (padding, 0),
+            Alignment::Centre => (padding / 2, padding - padding / 2),
+        };
+
+        write_spaces(writer, left)?;
+
+        if ellipsis && truncate_left {
+            writer.write_char(ELLIPSIS)?;
+        }
+
+        writer.write_str(text)?;
+
+        if ellipsis && !truncate_left {
+            writer.write_char(ELLIPSIS)?;
+        }
+
+        return write_spaces(writer, right);
+    }
+}
+
+/// Get the longest part of `text` at most `width` columns wide, keeping the end of the text if
+/// `from_left` is true and the start otherwise.
+fn truncate(text: &str, width: usize, from_left: bool) -> &str {
+    let mut used = 0;
+
+    if from_left {
+        let mut start = text.len();
+
+        for (idx, ch) in text.char_indices().rev() {
+            used += ch.width().unwrap_or(0);
+
+            if used > width {
+                break;
+            }
+
+            start = idx;
+        }
+
+        return &text[start..];
+    }
+
+    let mut end = 0;
+
+    for (idx, ch) in text.char_indices() {
+        used += ch.width().unwrap_or(0);
+
+        if used > width {
+            break;
+        }
+
+        end = idx + ch.len_utf8();
+    }
+
+    return &text[..end];
+}
+
+/// Write `count` spaces to `writer`.
+fn write_spaces<W: fmt::Write>(writer: &mut W, count: usize) -> fmt::Result {
+    for _ in 0..count {
+        writer.write_char(' ')?;
+    }
+
+    return Ok(());
+}
+
+impl Display for Layout {
+    /// Writes the layout in the pattern syntax, without the leading `|`.
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.alignment != Alignment::Left {
+            write!(f, "{}", self.alignment.as_char())?;
+        }
+
+        if let Some(min_width) = self.min_width {
+            write!(f, "{}", min_width)?;
+        }
+
+        if let Some(max_width) = self.max_width {
+            write!(f, ".{}", max_width)?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl FromStr for Layout {
+    type Err = ParseLayoutError;
+
+    /// Parses `[alignment][min_width][.max_width]` where the alignment is one of `<`, `>` or `^`,
+    /// at least one of the widths must be present.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseLayoutError {
+            layout: s.to_string(),
+        };
+        let mut layout = Layout::new();
+        let mut rest = s;
+
+        let alignment = match rest.chars().next() {
+            Some('<') => Some(Alignment::Left),
+            Some('>') => Some(Alignment::Right),
+            Some('^') => Some(Alignment::Centre),
+            _ => None,
+        };
+
+        if let Some(alignment) = alignment {
+            layout.alignment = alignment;
+            rest = &rest[1..];
+        }
+
+        let (min_width, max_width) = match rest.split_once('.') {
+            Some((min_width, max_width)) => (min_width, Some(max_width)),
+            None => (rest, None),
+        };
+
+        if !min_width.is_empty() {
+            layout.min_width = Some(parse_width(min_width).ok_or_else(error)?);
+        }
+
+        if let Some(max_width) = max_width {
+            layout.max_width = Some(parse_width(max_width).ok_or_else(error)?);
+        }
+
+        if layout.min_width.is_none() && layout.max_width.is_none() {
+            return Err(error());
+        }
+
+        return Ok(layout);
+    }
+}
+
+/// Parses a width made up only of ASCII digits.
+fn parse_width(s: &str) -> Option<usize> {
+    if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    return s.parse().ok();
+}
+
+impl ParseLayoutError {
+    /// The layout that could not be parsed.
+    pub fn layout(&self) -> &str {
+        return &self.layout;
+    }
+}
+
+impl Display for ParseLayoutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return write!(f, "invalid layout '{}'", self.layout);
+    }
+}
+
+impl std::error::Error for ParseLayoutError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::layout::{Alignment, Layout};
+
+    fn apply(layout: &str, text: &str, truncate_left: bool) -> String {
+        let mut output = String::new();
+        layout
+            .parse::<Layout>()
+            .unwrap()
+            .write(&mut output, text, truncate_left)
+            .unwrap();
+
+        return output;
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            ">20.20".parse(),
+            Ok(Layout::new()
+                .set_alignment(Alignment::Right)
+                .set_min_width(20)
+                .set_max_width(20))
+        );
+        assert_eq!("7".parse(), Ok(Layout::new().set_min_width(7)));
+        assert_eq!(".80".parse(), Ok(Layout::new().set_max_width(80)));
+
+        for invalid in ["", "^", "x", "<<7", "7.", "+7", "7.x"].iter() {
+            assert!(invalid.parse::<Layout>().is_err(), "{}", invalid);
+        }
+
+        for layout in [">20.20", "7", ".80", "^9"].iter() {
+            assert_eq!(&layout.parse::<Layout>().unwrap().to_string(), layout);
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(apply("<7", "Error", false), "Error  ");
+        assert_eq!(apply(">7", "Error", false), "  Error");
+        assert_eq!(apply("^8", "Error", false), " Error  ");
+        assert_eq!(apply("3", "Error", false), "Error");
+        assert_eq!(apply(".4", "Error", false), "Err…");
+        assert_eq!(apply(".4", "Error", true), "…ror");
+        assert_eq!(apply(".0", "Error", false), "");
+        assert_eq!(apply("5.5", "Error", false), "Error");
+    }
+
+    #[test]
+    fn test_apply_wide() {
+        // Each ideograph is two columns wide.
+        assert_eq!(apply("<6", "日本", false), "日本  ");
+        assert_eq!(apply(".4", "日本語", false), "日…");
+        assert_eq!(apply("4.4", "日本語", false), "日… ");
+        assert_eq!(apply(">6.6", "日本語テキスト", true), " …スト");
+    }
+}
@@ -4,33 +4,136 @@
 //! however it was designed from scratch, primarily for interest's sake but also because I wanted
 //! an interface I better understood and catered to my specific needs.
 //!
-//! Whilst most structs in this crate support custom timezones, it is not considered a principle
-//! focus and the support is mainly untested but possible if desired. The main intention is to use
-//! the [Local](chrono::Local) timezone where possible and by default.
+//! The formats and loggers in this crate support any timezone, including fixed offsets and, with the
+//! `chrono-tz` feature, named timezones, see the [timezone] module. The main intention is to use the
+//! [Local](chrono::Local) timezone where possible and by default.
+//!
+//! # Migrating from 0.1
+//! The level macros such as [info!] accept a format string followed by its arguments, so a logger
+//! following a string literal message is now read as a format argument and fails to compile with
+//! an unused argument error. Name the logger before the message instead.
+//! ```ignore
+//! // Before
+//! info!("pane closed", my_logger);
+//! // After
+//! info!(logger: my_logger, "pane closed");
+//! ```
+//! A message that isn't a string literal can still be followed by the logger, as in
+//! `info!(message, my_logger)`.
 
+pub mod clock;
+pub mod directive;
 pub mod format;
+mod json;
+pub mod layout;
 pub mod log;
 pub mod logger;
+mod process;
+pub mod rotation;
+mod strftime;
+pub mod style;
+pub mod timezone;
 #[macro_use]
 mod macros;
 
+#[cfg(feature = "chrono-tz")]
+pub use chrono_tz;
+
 // Internal undocumented methods used within the macros.
-pub use macros::{__default_log_message, __log_message};
+pub use macros::{
+    __default_log_message, __format_message, __log_message, __message_string,
+    __reject_named_arguments,
+};
 
 pub(crate) type DefaultLogger = FileLogger<chrono::Local>;
-use crate::log::LogLevel;
+use crate::log::{DynLogger, LevelFilter, LogLevel, Logger};
+use clock::{system_clock, Clock, SharedClock};
+use directive::Directives;
 use lazy_static::lazy_static;
 use logger::FileLogger;
+use rotation::{SizeRotation, TimeRotation};
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::{Mutex, RwLock};
 
 lazy_static! {
     /// The default logger. It is thread-safe and utilised by default in all the macros unless
-    /// otherwise specified..
+    /// otherwise specified or another logger is installed with [set_default_logger].
     ///
     /// Utilise the [set_output_file] and [close_output_file] to open and close respectively, the file
     /// used for logging.
-    pub static ref DEFAULT_LOGGER: Mutex<DefaultLogger> = Mutex::new(DefaultLogger::new());
+    pub static ref DEFAULT_LOGGER: Mutex<DefaultLogger> = {
+        let mut logger = DefaultLogger::new();
+        logger.set_level_filter(DEFAULT_LEVEL_FILTER.clone());
+
+        Mutex::new(logger)
+    };
+
+    /// The levels enabled by the default logger, shared with it so that the macros can reject
+    /// disabled levels without locking the default logger.
+    pub(crate) static ref DEFAULT_LEVEL_FILTER: LevelFilter = LevelFilter::new();
+
+    /// The levels enabled by the logger the macros are using, either [DEFAULT_LEVEL_FILTER] or the
+    /// filter of the logger installed with [set_default_logger]. There is no filter if the
+    /// installed logger doesn't have one, so every level is passed on to it.
+    pub(crate) static ref ENABLED_LEVELS: RwLock<Option<LevelFilter>> =
+        RwLock::new(Some(DEFAULT_LEVEL_FILTER.clone()));
+
+    /// The logger installed with [set_default_logger], used by the macros instead of
+    /// [DEFAULT_LOGGER].
+    pub(crate) static ref INSTALLED_LOGGER: Mutex<Option<Box<dyn DynLogger + Send>>> =
+        Mutex::new(None);
+
+    /// The clock of the logger the macros are using, either [DEFAULT_LOGGER] or the logger
+    /// installed with [set_default_logger], shared so that the macros can timestamp logs before
+    /// locking the logger.
+    pub(crate) static ref DEFAULT_CLOCK: RwLock<SharedClock> = RwLock::new(system_clock());
+}
+
+/// Install `logger` as the target of the macros that don't specify a logger, in place of the
+/// file logger [DEFAULT_LOGGER]. Any logger can be used once boxed, for example a
+/// [ConsoleLogger](logger::ConsoleLogger), [MultiLogger](logger::MultiLogger) or
+/// [AsyncLogger](logger::AsyncLogger).
+///
+/// Whilst a logger is installed, [set_output_file] and the other functions configuring the default
+/// logger still apply to [DEFAULT_LOGGER] but it receives no logs. Use [reset_default_logger] to
+/// return to it.
+///
+/// The macros reject levels disabled by the logger's [enabled_levels](log::Logger::enabled_levels)
+/// and read the time from its [clock](log::Logger::clock) without locking it. Both handles are
+/// kept from when the logger is installed, so changes to its levels are seen straight away but it
+/// should be reinstalled if it replaces its filter or clock.
+///
+/// # Example
+/// ```
+/// use muxide_logging::logger::ConsoleLogger;
+/// use muxide_logging::{info, reset_default_logger, set_default_logger};
+/// use chrono::Local;
+///
+/// set_default_logger(Box::new(ConsoleLogger::<Local>::new())).unwrap();
+/// info!("Written to stdout");
+/// reset_default_logger().unwrap();
+/// ```
+pub fn set_default_logger(logger: Box<dyn DynLogger + Send>) -> Result<(), String> {
+    let mut installed = INSTALLED_LOGGER.lock().map_err(|e| e.to_string())?;
+
+    *ENABLED_LEVELS.write().map_err(|e| e.to_string())? = logger.enabled_levels_dyn();
+    *DEFAULT_CLOCK.write().map_err(|e| e.to_string())? = logger.clock_dyn();
+    *installed = Some(logger);
+
+    return Ok(());
+}
+
+/// Remove the logger installed with [set_default_logger], returning it, so that the macros use
+/// [DEFAULT_LOGGER] again.
+pub fn reset_default_logger() -> Result<Option<Box<dyn DynLogger + Send>>, String> {
+    let mut installed = INSTALLED_LOGGER.lock().map_err(|e| e.to_string())?;
+    let logger = installed.take();
+
+    let default_logger = DEFAULT_LOGGER.lock().map_err(|e| e.to_string())?;
+    *ENABLED_LEVELS.write().map_err(|e| e.to_string())? = Some(DEFAULT_LEVEL_FILTER.clone());
+    *DEFAULT_CLOCK.write().map_err(|e| e.to_string())? = default_logger.clock();
+
+    return Ok(logger);
 }
 
 /// Set the default logger's output file.
@@ -44,6 +147,35 @@ pub fn set_output_file<P: AsRef<Path>>(path: P) -> Result<(), String> {
         .map_err(|e| e.to_string());
 }
 
+/// Set the default logger's output file, rotating it according to the specified [SizeRotation].
+/// Opens a new file to append new logs to. This method WILL block if another process is currently
+/// using the default logger.
+pub fn set_output_file_with_rotation<P: AsRef<Path>>(
+    path: P,
+    rotation: SizeRotation,
+) -> Result<(), String> {
+    return DEFAULT_LOGGER
+        .lock()
+        .map_err(|e| e.to_string())?
+        .open_file_with_rotation(path, rotation)
+        .map_err(|e| e.to_string());
+}
+
+/// Set the default logger's output file to a date-stamped path, starting a new file according to
+/// the specified [TimeRotation]. See
+/// [open_file_with_time_rotation](FileLogger::open_file_with_time_rotation) for the format of the
+/// pattern. This method WILL block if another process is currently using the default logger.
+pub fn set_output_file_with_time_rotation(
+    pattern: &str,
+    rotation: TimeRotation,
+) -> Result<(), String> {
+    return DEFAULT_LOGGER
+        .lock()
+        .map_err(|e| e.to_string())?
+        .open_file_with_time_rotation(pattern, rotation)
+        .map_err(|e| e.to_string());
+}
+
 /// Close the file opened by the default logger. This method WILL block if another process is
 /// currently using the default logger.
 pub fn close_output_file() -> Result<(), String> {
@@ -55,6 +187,25 @@ pub fn close_output_file() -> Result<(), String> {
     return Ok(());
 }
 
+/// Use `clock` to obtain the current time in the default logger, see
+/// [FileLogger::set_clock]. It is also used by [LogItem::new](log::LogItem::new) and by formats
+/// built without a timestamp, such as with [build_string](format::Format::build_string). A logger
+/// installed with [set_default_logger] uses its own clock.
+///
+/// Set the clock with this function rather than through [DEFAULT_LOGGER], as the macros read the
+/// time before locking the logger.
+pub fn set_clock<C: Clock + 'static>(clock: C) -> Result<(), String> {
+    let installed = INSTALLED_LOGGER.lock().map_err(|e| e.to_string())?;
+    let mut logger = DEFAULT_LOGGER.lock().map_err(|e| e.to_string())?;
+    logger.set_clock(clock);
+
+    if installed.is_none() {
+        *DEFAULT_CLOCK.write().map_err(|e| e.to_string())? = logger.clock();
+    }
+
+    return Ok(());
+}
+
 /// Prevents the default logger from logging logs at the specified levels.
 pub fn restrict_log_levels(prohibited_levels: &[LogLevel]) -> Result<(), String> {
     DEFAULT_LOGGER
@@ -75,3 +226,53 @@ pub fn allow_log_levels(allowed_log_levels: &[LogLevel]) -> Result<(), String> {
 
     return Ok(());
 }
+
+/// Prevents the default logger from logging logs less severe than `level`. Levels restricted with
+/// [restrict_log_levels] remain restricted.
+pub fn set_min_level(level: LogLevel) -> Result<(), String> {
+    DEFAULT_LOGGER
+        .lock()
+        .map_err(|e| e.to_string())?
+        .set_min_level(level);
+
+    return Ok(());
+}
+
+/// Removes the minimum level set with [set_min_level].
+pub fn clear_min_level() -> Result<(), String> {
+    DEFAULT_LOGGER
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clear_min_level();
+
+    return Ok(());
+}
+
+/// Use per-module minimum levels in the default logger, see [Directives].
+pub fn set_directives(directives: Directives) -> Result<(), String> {
+    DEFAULT_LOGGER
+        .lock()
+        .map_err(|e| e.to_string())?
+        .set_directives(directives);
+
+    return Ok(());
+}
+
+/// Removes the directives set with [set_directives] or [init_directives_from_env].
+pub fn clear_directives() -> Result<(), String> {
+    DEFAULT_LOGGER
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clear_directives();
+
+    return Ok(());
+}
+
+/// Use the directives in the `MUXIDE_LOG` environment variable in the default logger, for example
+/// `MUXIDE_LOG=warning,muxide::pty=information`. Nothing changes if the variable is not set.
+pub fn init_directives_from_env() -> Result<(), String> {
+    return match Directives::from_default_env() {
+        Some(directives) => set_directives(directives.map_err(|e| e.to_string())?),
+        None => Ok(()),
+    };
+}
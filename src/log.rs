@@ -1,16 +1,71 @@
 //! Log information
 
-use crate::format::Format;
-use chrono::{DateTime, Local, TimeZone, Utc};
+use crate::clock::{system_clock, Clock};
+use crate::format::{Encoding, Format, Record};
+use crate::style::ColourMode;
+use crate::timezone::now;
+use chrono::{DateTime, Duration, FixedOffset, Local, Offset, TimeZone, Utc};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
 use std::fmt::{self, Display, Formatter};
+use std::iter::FromIterator;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU16, AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
-#[derive(Copy, Clone, PartialEq, Debug, Hash)]
-/// The level of severity of a log message.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// The level of severity of a log message. Levels are ordered by their
+/// [severity](LogLevel::severity), so `LogLevel::Information < LogLevel::Error`.
 pub enum LogLevel {
+    /// Unrecoverable conditions, typically followed by the program exiting.
+    Critical,
     Error,
     Warning,
     StateChange,
     Information,
+    /// Diagnostics useful when debugging.
+    Debug,
+    /// Very detailed diagnostics, such as tracing the flow of a program.
+    Trace,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// The error returned when parsing an unknown [LogLevel] name.
+pub struct ParseLevelError {
+    name: String,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+/// The value of a structured field attached to a [LogItem].
+pub enum FieldValue {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Bool(bool),
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+/// An ordered set of key-value fields attached to a [LogItem]. Fields keep the order they were
+/// inserted in and inserting an existing key replaces its value in place.
+pub struct Fields {
+    entries: Vec<(String, FieldValue)>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// The information about a log that is known before it is built, used by
+/// [enabled](Logger::enabled) to cheaply reject logs.
+pub struct Metadata<'a> {
+    level: LogLevel,
+    module_path: &'a str,
+}
+
+#[derive(Clone, Debug)]
+/// A set of enabled log levels that can be shared between threads and checked without locking.
+/// Clones share the same state, so a logger can hand out its filter and later updates are seen by
+/// every clone.
+pub struct LevelFilter {
+    enabled: Arc<AtomicU16>,
 }
 
 #[derive(Clone, Debug)]
@@ -22,16 +77,36 @@ where
     DateTime<Tz>: Copy,
 {
     format: Format<Tz>,
-    message: String,
+    /// Borrowed when the message is a string literal, so logging it doesn't allocate.
+    message: Cow<'static, str>,
     level: LogLevel,
+    fields: Fields,
+    /// The time the log was created.
+    timestamp: DateTime<Tz>,
+    /// The position of the log in the order logs were created.
+    sequence: u64,
+    /// The time between the logger starting and the log being created.
+    elapsed: Option<Duration>,
+    /// The time between the logger's previous log and this log being created.
+    since_last: Option<Duration>,
 }
 
+/// The sequence number of the next [LogItem] to be created.
+static NEXT_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
 /// Defines the expected behaviour of a logger. It is required for using any of the macros
 /// supplied by this library.
 pub trait Logger {
     /// The return type from performing [log_item](Logger::log_item).
     type ReturnType;
 
+    /// Returns true if a log with this metadata may be permitted. This is checked by the macros
+    /// before the format, fields and message are built so it should be cheap, for example only
+    /// checking the level. By default this is true.
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        return true;
+    }
+
     /// Returns true if logging an item is permitted. By default this is true but when implementing
     /// a custom logger it may be useful to decline logging an item before the
     /// [log_item](Logger::log_item) method is called.
@@ -45,6 +120,26 @@ pub trait Logger {
         return true;
     }
 
+    /// Get the current time according to the logger's [Clock], used by the
+    /// macros to timestamp each log when it is created. By default this is the system time.
+    fn now(&self) -> DateTime<Utc> {
+        return Utc::now();
+    }
+
+    /// Get a handle to the logger's [Clock], allowing the time to be read
+    /// without access to the logger. By default this is the
+    /// [SystemClock](crate::clock::SystemClock), which agrees with the default [now](Logger::now).
+    fn clock(&self) -> Arc<dyn Clock> {
+        return system_clock();
+    }
+
+    /// Get a handle to the filter of levels that [enabled](Logger::enabled) may accept, allowing
+    /// loggers wrapping this one to reject disabled levels without access to it. By default there
+    /// is no filter, so every level may be accepted.
+    fn enabled_levels(&self) -> Option<LevelFilter> {
+        return None;
+    }
+
     /// This method should log an item.
     fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
     where
@@ -54,7 +149,280 @@ pub trait Logger {
         DateTime<Tz>: Copy;
 }
 
+/// An object safe form of [Logger] that works with logs in a fixed offset timezone, allowing
+/// loggers of different types to be boxed together. Every [Logger] implements it, discarding the
+/// return value of [log_item](Logger::log_item), and `dyn DynLogger` implements [Logger] in turn
+/// so trait objects can be used with the macros.
+///
+/// [Logger] is also implemented for `Box<L>`, `&mut L` and `Arc<Mutex<L>>` of any logger.
+///
+/// # Example
+/// ```
+/// use muxide_logging::info;
+/// use muxide_logging::log::DynLogger;
+/// use muxide_logging::logger::{ConsoleLogger, StringLogger};
+/// use chrono::{Local, Utc};
+///
+/// let mut loggers: Vec<Box<dyn DynLogger>> = vec![
+///     Box::new(ConsoleLogger::<Local>::new()),
+///     Box::new(StringLogger::<Utc>::new_tz()),
+/// ];
+///
+/// for logger in loggers.iter_mut() {
+///     info!(logger: *logger, "Log message");
+/// }
+/// ```
+pub trait DynLogger {
+    /// See [Logger::enabled].
+    fn enabled_dyn(&self, metadata: &Metadata) -> bool;
+
+    /// See [Logger::can_log_item].
+    fn can_log_dyn(&self, item: &LogItem<FixedOffset>) -> bool;
+
+    /// See [Logger::now].
+    fn now_dyn(&self) -> DateTime<Utc>;
+
+    /// See [Logger::clock].
+    fn clock_dyn(&self) -> Arc<dyn Clock>;
+
+    /// See [Logger::enabled_levels].
+    fn enabled_levels_dyn(&self) -> Option<LevelFilter>;
+
+    /// See [Logger::log_item].
+    fn log_dyn(&mut self, item: LogItem<FixedOffset>);
+}
+
+impl<L: Logger> DynLogger for L {
+    fn enabled_dyn(&self, metadata: &Metadata) -> bool {
+        return self.enabled(metadata);
+    }
+
+    fn can_log_dyn(&self, item: &LogItem<FixedOffset>) -> bool {
+        return self.can_log_item(item);
+    }
+
+    fn now_dyn(&self) -> DateTime<Utc> {
+        return self.now();
+    }
+
+    fn clock_dyn(&self) -> Arc<dyn Clock> {
+        return self.clock();
+    }
+
+    fn enabled_levels_dyn(&self) -> Option<LevelFilter> {
+        return self.enabled_levels();
+    }
+
+    fn log_dyn(&mut self, item: LogItem<FixedOffset>) {
+        let _ = self.log_item(item);
+    }
+}
+
+/// Implements [Logger] for a [DynLogger] trait object, converting each log to a fixed offset
+/// timezone.
+macro_rules! impl_logger_for_dyn {
+    ($dyn_type:ty) => {
+        impl Logger for $dyn_type {
+            type ReturnType = ();
+
+            fn enabled(&self, metadata: &Metadata) -> bool {
+                return self.enabled_dyn(metadata);
+            }
+
+            fn can_log_item<Tz: TimeZone>(&self, item: &LogItem<Tz>) -> bool
+            where
+                Tz::Offset: std::fmt::Display,
+                DateTime<Local>: From<DateTime<Tz>>,
+                DateTime<Utc>: From<DateTime<Tz>>,
+                DateTime<Tz>: Copy,
+            {
+                return self.can_log_dyn(&item.to_fixed_offset());
+            }
+
+            fn now(&self) -> DateTime<Utc> {
+                return self.now_dyn();
+            }
+
+            fn clock(&self) -> Arc<dyn Clock> {
+                return self.clock_dyn();
+            }
+
+            fn enabled_levels(&self) -> Option<LevelFilter> {
+                return self.enabled_levels_dyn();
+            }
+
+            fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+            where
+                Tz::Offset: std::fmt::Display,
+                DateTime<Local>: From<DateTime<Tz>>,
+                DateTime<Utc>: From<DateTime<Tz>>,
+                DateTime<Tz>: Copy,
+            {
+                self.log_dyn(item.into_fixed_offset());
+            }
+        }
+    };
+}
+
+impl_logger_for_dyn!(dyn DynLogger);
+impl_logger_for_dyn!(dyn DynLogger + Send);
+
+impl<L: Logger + ?Sized> Logger for Box<L> {
+    type ReturnType = L::ReturnType;
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return (**self).enabled(metadata);
+    }
+
+    fn can_log_item<Tz: TimeZone>(&self, item: &LogItem<Tz>) -> bool
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        return (**self).can_log_item(item);
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        return (**self).now();
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        return (**self).clock();
+    }
+
+    fn enabled_levels(&self) -> Option<LevelFilter> {
+        return (**self).enabled_levels();
+    }
+
+    fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        return (**self).log_item(item);
+    }
+}
+
+impl<L: Logger + ?Sized> Logger for &mut L {
+    type ReturnType = L::ReturnType;
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return (**self).enabled(metadata);
+    }
+
+    fn can_log_item<Tz: TimeZone>(&self, item: &LogItem<Tz>) -> bool
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        return (**self).can_log_item(item);
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        return (**self).now();
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        return (**self).clock();
+    }
+
+    fn enabled_levels(&self) -> Option<LevelFilter> {
+        return (**self).enabled_levels();
+    }
+
+    fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        return (**self).log_item(item);
+    }
+}
+
+/// Shares a logger between owners, locking it for each call. A poisoned lock is still used since
+/// a panic whilst logging leaves nothing half written that later logs depend on.
+impl<L: Logger + ?Sized> Logger for Arc<Mutex<L>> {
+    type ReturnType = L::ReturnType;
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return lock(self).enabled(metadata);
+    }
+
+    fn can_log_item<Tz: TimeZone>(&self, item: &LogItem<Tz>) -> bool
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        return lock(self).can_log_item(item);
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        return lock(self).now();
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        return lock(self).clock();
+    }
+
+    fn enabled_levels(&self) -> Option<LevelFilter> {
+        return lock(self).enabled_levels();
+    }
+
+    fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        return lock(self).log_item(item);
+    }
+}
+
+fn lock<L: ?Sized>(logger: &Mutex<L>) -> MutexGuard<'_, L> {
+    return logger.lock().unwrap_or_else(PoisonError::into_inner);
+}
+
 impl LogLevel {
+    /// Every level, ordered from the least to the most severe.
+    pub const ALL: [LogLevel; 7] = [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Information,
+        LogLevel::StateChange,
+        LogLevel::Warning,
+        LogLevel::Error,
+        LogLevel::Critical,
+    ];
+
+    /// The numeric severity of a level, higher values are more severe.
+    pub const fn severity(&self) -> u8 {
+        return match self {
+            LogLevel::Trace => 0,
+            LogLevel::Debug => 10,
+            LogLevel::Information => 20,
+            LogLevel::StateChange => 30,
+            LogLevel::Warning => 40,
+            LogLevel::Error => 50,
+            LogLevel::Critical => 60,
+        };
+    }
+
+    /// The bit representing this level in a [LevelFilter].
+    const fn bit(&self) -> u16 {
+        return 1 << (self.severity() / 10);
+    }
+
     /// Converts a [LogLevel] variant into a string.
     pub const fn as_str(&self) -> &'static str {
         return match self {
@@ -62,10 +430,61 @@ impl LogLevel {
             LogLevel::Warning => "Warning",
             LogLevel::StateChange => "StateChange",
             LogLevel::Information => "Information",
+            LogLevel::Debug => "Debug",
+            LogLevel::Trace => "Trace",
+            LogLevel::Critical => "Critical",
+        };
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = ParseLevelError;
+
+    /// Parses a level from its name, ignoring case. The names returned by
+    /// [as_str](LogLevel::as_str) are accepted along with `info`, `warn` and `state_change`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_ascii_lowercase().as_str() {
+            "trace" => Ok(LogLevel::Trace),
+            "debug" => Ok(LogLevel::Debug),
+            "information" | "info" => Ok(LogLevel::Information),
+            "statechange" | "state_change" => Ok(LogLevel::StateChange),
+            "warning" | "warn" => Ok(LogLevel::Warning),
+            "error" => Ok(LogLevel::Error),
+            "critical" => Ok(LogLevel::Critical),
+            _ => Err(ParseLevelError {
+                name: s.to_string(),
+            }),
         };
     }
 }
 
+impl ParseLevelError {
+    /// The name that could not be parsed.
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+}
+
+impl Display for ParseLevelError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return write!(f, "unknown log level '{}'", self.name);
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+impl PartialOrd for LogLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+impl Ord for LogLevel {
+    fn cmp(&self, other: &Self) -> Ordering {
+        return self.severity().cmp(&other.severity());
+    }
+}
+
 impl Display for LogLevel {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         return write!(f, "{}", self.as_str());
@@ -75,40 +494,261 @@ impl Display for LogLevel {
 impl<Tz: TimeZone> LogItem<Tz>
 where
     Tz::Offset: std::fmt::Display,
-    DateTime<Local>: From<DateTime<Tz>>,
-    DateTime<Utc>: From<DateTime<Tz>>,
     DateTime<Tz>: Copy,
 {
-    /// Create a new [LogItem].
+    /// Create a new [LogItem], recording the current time in the timezone of the format as its
+    /// [timestamp](LogItem::timestamp). The time is read from the clock of the default logger, see
+    /// [set_clock](crate::set_clock).
     pub fn new(format: Format<Tz>, level: LogLevel, message: &str) -> Self {
+        let timestamp = now(format.timezone());
+
+        return Self::new_at(format, level, message, timestamp);
+    }
+
+    /// Create a new [LogItem] created at `timestamp`, for example the time of a logger's
+    /// [Clock].
+    pub fn new_at(
+        format: Format<Tz>,
+        level: LogLevel,
+        message: &str,
+        timestamp: DateTime<Tz>,
+    ) -> Self {
         return Self {
             format,
-            message: message.to_string(),
+            message: Cow::Owned(message.to_string()),
             level,
+            fields: Fields::new(),
+            timestamp,
+            sequence: NEXT_SEQUENCE.fetch_add(1, AtomicOrdering::Relaxed),
+            elapsed: None,
+            since_last: None,
         };
     }
 
+    /// Replace the message of this log, either with a [String] or a static string.
+    pub fn with_message<M: Into<Cow<'static, str>>>(mut self, message: M) -> Self {
+        self.message = message.into();
+
+        return self;
+    }
+
+    /// Replace the format of this log.
+    pub fn with_format(mut self, format: Format<Tz>) -> Self {
+        self.format = format;
+
+        return self;
+    }
+
+    /// Add a structured field to this log, replacing any existing field with the same key.
+    pub fn with_field<V: Into<FieldValue>>(mut self, key: &str, value: V) -> Self {
+        self.fields.insert(key, value);
+
+        return self;
+    }
+
+    /// Replace the time the log was created.
+    pub fn with_timestamp(mut self, timestamp: DateTime<Tz>) -> Self {
+        self.timestamp = timestamp;
+
+        return self;
+    }
+
+    /// Set the time between the logger starting and the log being created, used by
+    /// [FormatItem::Elapsed](crate::format::FormatItem::Elapsed).
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.elapsed = Some(elapsed);
+
+        return self;
+    }
+
+    /// Set the time between the logger's previous log and this log being created, used by
+    /// [FormatItem::SinceLast](crate::format::FormatItem::SinceLast).
+    pub fn with_since_last(mut self, since_last: Duration) -> Self {
+        self.since_last = Some(since_last);
+
+        return self;
+    }
+
+    /// Replace all of the structured fields of this log.
+    pub fn with_fields(mut self, fields: Fields) -> Self {
+        self.fields = fields;
+
+        return self;
+    }
+
+    /// Set whether the styling items of this log's format produce escape codes, see
+    /// [Format::set_colour_mode].
+    pub fn with_colour_mode(mut self, mode: ColourMode) -> Self {
+        self.format = self.format.set_colour_mode(mode);
+
+        return self;
+    }
+
+    /// Get the structured fields of this log.
+    pub fn fields(&self) -> &Fields {
+        return &self.fields;
+    }
+
     /// Get the log level of this log.
     pub fn level(&self) -> LogLevel {
         return self.level;
     }
 
     /// Get the log message of this log.
-    pub fn message(&self) -> &String {
+    pub fn message(&self) -> &str {
         return &self.message;
     }
 
     /// Consume a [LogItem], returning the message.
     pub fn into_message(self) -> String {
-        return self.message;
+        return self.message.into_owned();
     }
 
     /// Get the format of this log.
     pub fn format(&self) -> &Format<Tz> {
         return &self.format;
     }
+
+    /// Get the time the log was created. This is the time used when building the log unless the
+    /// format has a [constant time](Format::set_constant_time).
+    pub fn timestamp(&self) -> DateTime<Tz> {
+        return self.timestamp;
+    }
+
+    /// Get the sequence number of this log. Every log is numbered when it is created from a
+    /// counter shared by the whole process, so later logs always have a greater number.
+    pub fn sequence(&self) -> u64 {
+        return self.sequence;
+    }
+
+    /// Get the time between the logger starting and the log being created, if the logger has set
+    /// it.
+    pub fn elapsed(&self) -> Option<Duration> {
+        return self.elapsed;
+    }
+
+    /// Get the time between the logger's previous log and this log being created, if the logger
+    /// has set it.
+    pub fn since_last(&self) -> Option<Duration> {
+        return self.since_last;
+    }
+
+    /// Convert the log into one using `timezone`, see [Format::with_timezone].
+    pub fn with_timezone<T2: TimeZone>(self, timezone: T2) -> LogItem<T2>
+    where
+        T2::Offset: std::fmt::Display,
+        DateTime<T2>: Copy,
+    {
+        return LogItem::<T2> {
+            timestamp: self.timestamp.with_timezone(&timezone),
+            format: self.format.with_timezone(timezone),
+            message: self.message,
+            level: self.level,
+            fields: self.fields,
+            sequence: self.sequence,
+            elapsed: self.elapsed,
+            since_last: self.since_last,
+        };
+    }
+
+    /// Convert the log into one with a fixed offset timezone, keeping the offset of any constant
+    /// time or otherwise of the timestamp.
+    pub fn into_fixed_offset(self) -> LogItem<FixedOffset> {
+        let offset = self
+            .format
+            .constant_time()
+            .unwrap_or(self.timestamp)
+            .offset()
+            .fix();
+
+        return self.with_timezone::<FixedOffset>(offset);
+    }
+
+    /// Same as [into_fixed_offset](LogItem::into_fixed_offset) but without consuming the log.
+    pub fn to_fixed_offset(&self) -> LogItem<FixedOffset> {
+        let offset = self
+            .format
+            .constant_time()
+            .unwrap_or(self.timestamp)
+            .offset()
+            .fix();
+
+        return LogItem::<FixedOffset> {
+            timestamp: self.timestamp.with_timezone(&offset),
+            format: self.format.to_timezone::<FixedOffset>(offset),
+            message: self.message.clone(),
+            level: self.level,
+            fields: self.fields.clone(),
+            sequence: self.sequence,
+            elapsed: self.elapsed,
+            since_last: self.since_last,
+        };
+    }
+
+    /// Consume a [LogItem], building its format with the specified [Encoding].
+    pub fn build(self, encoding: Encoding) -> String {
+        return self.format.build_record(encoding, &self.record());
+    }
+
+    /// Get the values the format of the log is built with.
+    pub(crate) fn record(&self) -> Record<'_, Tz> {
+        return Record {
+            timestamp: self.timestamp,
+            level: self.level,
+            message: &self.message,
+            fields: &self.fields,
+            sequence: Some(self.sequence),
+            elapsed: self.elapsed,
+            since_last: self.since_last,
+        };
+    }
+}
+
+impl<'a> Metadata<'a> {
+    /// Create new [Metadata].
+    pub const fn new(level: LogLevel, module_path: &'a str) -> Self {
+        return Self { level, module_path };
+    }
+
+    /// Get the log level of the log.
+    pub const fn level(&self) -> LogLevel {
+        return self.level;
+    }
+
+    /// Get the module where the log originated.
+    pub const fn module_path(&self) -> &'a str {
+        return self.module_path;
+    }
 }
 
+impl LevelFilter {
+    /// Create a new [LevelFilter] with every level enabled.
+    pub fn new() -> Self {
+        return Self {
+            enabled: Arc::new(AtomicU16::new(u16::MAX)),
+        };
+    }
+
+    /// Returns true if `level` is enabled.
+    pub fn is_enabled(&self, level: LogLevel) -> bool {
+        return self.enabled.load(AtomicOrdering::Relaxed) & level.bit() != 0;
+    }
+
+    /// Enable exactly the specified levels, disabling any others.
+    pub fn set_enabled<I: IntoIterator<Item = LogLevel>>(&self, levels: I) {
+        let mask = levels.into_iter().fold(0, |mask, level| mask | level.bit());
+
+        self.enabled.store(mask, AtomicOrdering::Relaxed);
+    }
+}
+
+impl Default for LevelFilter {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+#[allow(clippy::from_over_into)]
 impl<Tz: TimeZone> Into<String> for LogItem<Tz>
 where
     Tz::Offset: std::fmt::Display,
@@ -116,6 +756,156 @@ where
 {
     /// Builds the format and returns the built string.
     fn into(self) -> String {
-        return self.format.build_string(self.level, &self.message);
+        return self.build(Encoding::Text);
+    }
+}
+
+impl Fields {
+    /// Create an empty set of fields.
+    pub fn new() -> Self {
+        return Self {
+            entries: Vec::new(),
+        };
+    }
+
+    /// Insert a field, replacing the value of an existing field with the same key whilst keeping
+    /// its position.
+    pub fn insert<V: Into<FieldValue>>(&mut self, key: &str, value: V) {
+        let value = value.into();
+
+        match self.entries.iter_mut().find(|(k, _)| k == key) {
+            Some(entry) => entry.1 = value,
+            None => self.entries.push((key.to_string(), value)),
+        }
+    }
+
+    /// Get the value of the field with the specified key.
+    pub fn get(&self, key: &str) -> Option<&FieldValue> {
+        return self
+            .entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, value)| value);
+    }
+
+    /// Iterate over the fields in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FieldValue)> {
+        return self.entries.iter().map(|(k, v)| (k.as_str(), v));
+    }
+
+    /// The number of fields.
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    /// Returns true if there are no fields.
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+}
+
+impl<K: AsRef<str>, V: Into<FieldValue>> FromIterator<(K, V)> for Fields {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut fields = Fields::new();
+
+        for (key, value) in iter {
+            fields.insert(key.as_ref(), value);
+        }
+
+        return fields;
     }
 }
+
+impl FieldValue {
+    /// Write the value as it appears in a `key=value` pair. Strings that are empty or contain
+    /// whitespace, quotes or `=` are quoted so the pair can be read back unambiguously.
+    pub(crate) fn write_pair<W: fmt::Write>(&self, writer: &mut W) -> fmt::Result {
+        return match self {
+            FieldValue::String(s)
+                if s.is_empty()
+                    || s.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') =>
+            {
+                write!(writer, "{:?}", s)
+            }
+            _ => write!(writer, "{}", self),
+        };
+    }
+}
+
+impl Display for FieldValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return match self {
+            FieldValue::String(s) => f.write_str(s),
+            FieldValue::Integer(i) => write!(f, "{}", i),
+            FieldValue::Float(x) => write!(f, "{}", x),
+            FieldValue::Bool(b) => write!(f, "{}", b),
+        };
+    }
+}
+
+impl From<&str> for FieldValue {
+    fn from(s: &str) -> Self {
+        return FieldValue::String(s.to_string());
+    }
+}
+
+impl From<String> for FieldValue {
+    fn from(s: String) -> Self {
+        return FieldValue::String(s);
+    }
+}
+
+impl From<&String> for FieldValue {
+    fn from(s: &String) -> Self {
+        return FieldValue::String(s.clone());
+    }
+}
+
+impl From<bool> for FieldValue {
+    fn from(b: bool) -> Self {
+        return FieldValue::Bool(b);
+    }
+}
+
+impl From<f32> for FieldValue {
+    fn from(x: f32) -> Self {
+        return FieldValue::Float(x.into());
+    }
+}
+
+impl From<f64> for FieldValue {
+    fn from(x: f64) -> Self {
+        return FieldValue::Float(x);
+    }
+}
+
+macro_rules! impl_integer_field_value {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for FieldValue {
+                fn from(i: $t) -> Self {
+                    return FieldValue::Integer(i.into());
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_field_value!(i8, i16, i32, i64, u8, u16, u32);
+
+macro_rules! impl_wide_integer_field_value {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for FieldValue {
+                /// Values that do not fit in an [i64] saturate at [i64::MAX] or [i64::MIN].
+                fn from(i: $t) -> Self {
+                    let saturated = if i > (0 as $t) { i64::MAX } else { i64::MIN };
+
+                    return FieldValue::Integer(i64::try_from(i).unwrap_or(saturated));
+                }
+            }
+        )*
+    };
+}
+
+impl_wide_integer_field_value!(u64, isize, usize);
@@ -1,18 +1,39 @@
 //! Loggers useful for various types of logging.
 
-use crate::format::Format;
-use crate::log::{LogItem, LogLevel, Logger};
+mod async_logger;
+mod console_logger;
+mod levels;
+mod multi_logger;
+mod timer;
+
+pub use async_logger::{AsyncLogger, OverflowPolicy};
+pub use console_logger::{ConsoleLogger, Stream};
+pub use multi_logger::{MultiLogger, Sink};
+
+use levels::LevelSettings;
+use timer::Timer;
+
+use crate::clock::{system_clock, Clock};
+use crate::directive::Directives;
+use crate::format::{Encoding, Format};
+use crate::log::{LevelFilter, LogItem, LogLevel, Logger, Metadata};
+use crate::rotation::{expand_path, validate_pattern, SizeRotation, TimeRotation};
+use crate::style::ColourMode;
+use crate::timezone::DefaultTimeZone;
 use chrono::{DateTime, Local, TimeZone, Utc};
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[derive(Debug)]
 /// The default logger, writes any new logs to a file by appending.
 ///
+/// Styling items in formats never produce escape codes in the file.
+///
 /// ## Using FileLogger
-/// `FileLogger` can be used through the [error!], [warning!], [state_change!] and [info!] macros
-/// which utilise the [Logger] trait. However it can also be used manually.
+/// `FileLogger` can be used through the [critical!], [error!], [warning!], [state_change!],
+/// [info!], [debug!] and [trace!] macros which utilise the [Logger] trait. However it can also be used manually.
 ///
 /// ```no_run
 /// use muxide_logging::logger::FileLogger;
@@ -32,12 +53,32 @@ where
 {
     /// The file to write to. We have an optional value so that the user can open a file on demand.
     file: Option<File>,
+    /// The path of the open file, kept so that the file can be reopened when rotating.
+    path: Option<PathBuf>,
+    /// The size of the open file in bytes.
+    file_size: u64,
+    /// When to move the open file aside and start a new one.
+    rotation: Option<SizeRotation>,
+    /// When to start a new file with a date-stamped path.
+    time_rotation: Option<TimeRotation>,
+    /// The path containing strftime patterns used with the time rotation.
+    path_pattern: Option<String>,
+    /// The rotation period of the open file.
+    period_key: Option<(i32, u32, u32)>,
     /// Whether we should panic on IO errors or ignore them.
     panic_on_fail: bool,
     /// A custom Format to use as an override.
     override_format: Option<Format<Tz>>,
-    /// Any logs with these log levels will be ignored.
-    restricted_log_levels: Vec<LogLevel>,
+    /// The timezone logs are written in and the current time is obtained in.
+    timezone: Tz,
+    /// The source of the current time, used to timestamp logs and for time based rotation.
+    timer: Timer,
+    /// The restricted levels, minimum level and directives deciding which logs are written.
+    levels: LevelSettings,
+    /// How each log is encoded before being written.
+    encoding: Encoding,
+    /// Reused to build each line so that logging doesn't allocate.
+    buffer: String,
 }
 
 #[derive(Clone, Debug)]
@@ -45,8 +86,8 @@ where
 /// it will return a string when using each logging macro.
 ///
 /// ## Using StringLogger
-/// `FileLogger` can be used through the [error!], [warning!], [state_change!] and [info!] macros
-/// which utilise the [Logger] trait. However it can also be used manually.
+/// `FileLogger` can be used through the [critical!], [error!], [warning!], [state_change!],
+/// [info!], [debug!] and [trace!] macros which utilise the [Logger] trait. However it can also be used manually.
 ///
 /// ```
 /// use muxide_logging::logger::StringLogger;
@@ -68,23 +109,68 @@ where
     DateTime<Tz>: Copy,
 {
     override_format: Option<Format<Tz>>,
+    timezone: Tz,
+    timer: Timer,
+    encoding: Encoding,
 }
 
-impl<Tz: TimeZone> FileLogger<Tz>
+impl<Tz: DefaultTimeZone> FileLogger<Tz>
 where
     Tz::Offset: std::fmt::Display,
     DateTime<Tz>: Copy,
 {
     /// Create a new instance of [FileLogger].
+    #[allow(clippy::new_without_default)]
     pub fn new() -> FileLogger<Tz> {
+        return Self::new_in(Tz::default_timezone());
+    }
+}
+
+impl<Tz: TimeZone> FileLogger<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    /// Create a new instance of [FileLogger] that writes logs in `timezone`, for timezones such
+    /// as [FixedOffset](chrono::FixedOffset) that need configuring.
+    pub fn new_in(timezone: Tz) -> FileLogger<Tz> {
         return Self {
             file: None,
+            path: None,
+            file_size: 0,
+            rotation: None,
+            time_rotation: None,
+            path_pattern: None,
+            period_key: None,
             panic_on_fail: false,
             override_format: None,
-            restricted_log_levels: Vec::new(),
+            timezone,
+            timer: Timer::new(system_clock()),
+            levels: LevelSettings::new(),
+            encoding: Encoding::Text,
+            buffer: String::new(),
         };
     }
 
+    /// Get the timezone logs are written in.
+    pub fn timezone(&self) -> &Tz {
+        return &self.timezone;
+    }
+
+    /// Use `clock` instead of the [SystemClock](crate::clock::SystemClock) to obtain the time. The
+    /// clock timestamps logs created by the macros and decides when a file opened with
+    /// [open_file_with_time_rotation](FileLogger::open_file_with_time_rotation) is rotated and
+    /// which previous files have expired. The time [elapsed](crate::format::FormatItem::Elapsed)
+    /// is measured from when the clock is set.
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        self.timer.set_clock(clock);
+    }
+
+    /// Get the current time in the logger's timezone.
+    fn current_time(&self) -> DateTime<Tz> {
+        return self.timer.now().with_timezone(&self.timezone);
+    }
+
     /// Sets whether a failed write to a file should result in a panic. By default this behaviour is
     /// disabled.
     pub fn set_panic_on_fail(&mut self, b: bool) {
@@ -98,9 +184,69 @@ where
         self.override_format = Some(override_format);
     }
 
+    /// Set the [Encoding] used to write each log. Using [Encoding::Json] writes one JSON object
+    /// per line, the location metadata is still taken from the merged override format.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
     /// Open a file for logging in append mode, creating a new one if it doesn't exist.
     pub fn open_file<P: AsRef<Path>>(&mut self, path: P) -> std::io::Result<()> {
-        self.file = Some(OpenOptions::new().append(true).create(true).open(path)?);
+        self.clear_rotation();
+
+        return self.open_path(path.as_ref().to_path_buf());
+    }
+
+    /// Open a file for logging in append mode, creating a new one if it doesn't exist, that is
+    /// rotated according to the specified [SizeRotation]. Rotation happens between logs so a
+    /// single log is never split across files.
+    pub fn open_file_with_rotation<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        rotation: SizeRotation,
+    ) -> std::io::Result<()> {
+        self.clear_rotation();
+        self.rotation = Some(rotation);
+
+        return self.open_path(path.as_ref().to_path_buf());
+    }
+
+    /// Open a date-stamped file for logging in append mode, creating a new one if it doesn't
+    /// exist. The path is built by expanding the strftime patterns in `pattern`, such as
+    /// `muxide-%Y-%m-%d.log`, and a new file is started according to the specified
+    /// [TimeRotation]. Rotation happens between logs so a single log is never split across files.
+    pub fn open_file_with_time_rotation(
+        &mut self,
+        pattern: &str,
+        rotation: TimeRotation,
+    ) -> std::io::Result<()> {
+        validate_pattern(pattern)?;
+
+        let now = self.current_time();
+        let path = expand_path(pattern, &now);
+
+        self.clear_rotation();
+        self.open_path(path.clone())?;
+        self.time_rotation = Some(rotation);
+        self.path_pattern = Some(pattern.to_string());
+        self.period_key = Some(rotation.period_key(&now));
+
+        return rotation.remove_expired(pattern, &path, &now);
+    }
+
+    fn clear_rotation(&mut self) {
+        self.rotation = None;
+        self.time_rotation = None;
+        self.path_pattern = None;
+        self.period_key = None;
+    }
+
+    fn open_path(&mut self, path: PathBuf) -> std::io::Result<()> {
+        let file = OpenOptions::new().append(true).create(true).open(&path)?;
+
+        self.file_size = file.metadata()?.len();
+        self.file = Some(file);
+        self.path = Some(path);
 
         return Ok(());
     }
@@ -108,59 +254,99 @@ where
     /// Close the file that is currently open.
     pub fn close_file(&mut self) {
         self.file = None;
+        self.path = None;
+        self.file_size = 0;
     }
 
-    /// Prevent logging any messages with these log levels
-    pub fn restrict_log_levels(&mut self, levels: &[LogLevel]) {
-        for level in levels {
-            if !self.restricted_log_levels.contains(level) {
-                self.restricted_log_levels.push(*level);
-            }
-        }
+    /// Get the size based rotation policy of the open file, if any.
+    pub fn rotation(&self) -> Option<SizeRotation> {
+        return self.rotation;
     }
 
-    /// Allow any previously restricted log level.
-    pub fn allow_log_levels(&mut self, levels: &[LogLevel]) {
-        for level in levels {
-            if let Some(idx) = self.restricted_log_levels.iter().position(|l| level == l) {
-                self.restricted_log_levels.remove(idx);
+    /// Get the time based rotation policy of the open file, if any.
+    pub fn time_rotation(&self) -> Option<TimeRotation> {
+        return self.time_rotation;
+    }
+
+    /// Get the path of the open file.
+    pub fn path(&self) -> Option<&Path> {
+        return self.path.as_deref();
+    }
+
+    /// Rotate the open file if writing `len` more bytes at the time `now` would exceed the
+    /// rotation policy.
+    fn rotate_if_needed(&mut self, len: u64, now: &DateTime<Tz>) -> std::io::Result<()> {
+        if let (Some(rotation), Some(pattern)) = (self.time_rotation, self.path_pattern.as_ref()) {
+            let period_key = rotation.period_key(now);
+
+            if self.period_key == Some(period_key) {
+                return Ok(());
             }
+
+            let pattern = pattern.clone();
+            let path = expand_path(&pattern, now);
+
+            // The period only advances once its file is open, so a failed open is retried.
+            self.file = None;
+            self.open_path(path.clone())?;
+            self.period_key = Some(period_key);
+
+            return rotation.remove_expired(&pattern, &path, now);
         }
-    }
-}
 
-impl Logger for FileLogger<Local> {
-    type ReturnType = ();
+        if self.file.is_none() {
+            // The file couldn't be reopened after the previous rotation, so try again.
+            return match self.path.clone() {
+                Some(path) => self.open_path(path),
+                None => Ok(()),
+            };
+        }
 
-    fn can_log_item<Tz: TimeZone>(&self, item: &LogItem<Tz>) -> bool
-    where
-        Tz::Offset: std::fmt::Display,
-        DateTime<Local>: From<DateTime<Tz>>,
-        DateTime<Utc>: From<DateTime<Tz>>,
-        DateTime<Tz>: Copy,
-    {
-        return !self.restricted_log_levels.contains(&item.level());
+        let (rotation, path) = match (self.rotation, self.path.as_ref()) {
+            (Some(rotation), Some(path)) if rotation.should_rotate(self.file_size, len) => {
+                (rotation, path.clone())
+            }
+            _ => return Ok(()),
+        };
+
+        // Close our handle before moving the file so that rotation also works on Windows.
+        self.file = None;
+        self.file_size = 0;
+        let res = rotation.rotate(&path);
+
+        // Always try to reopen, if the rotation failed we continue appending to the old file.
+        self.open_path(path)?;
+
+        return res;
     }
 
-    fn log_item<T: TimeZone>(&mut self, item: LogItem<T>) -> Self::ReturnType
-    where
-        T::Offset: std::fmt::Display,
-        DateTime<Local>: From<DateTime<T>>,
-        DateTime<Utc>: From<DateTime<T>>,
-        DateTime<T>: Copy,
-    {
-        if let Some(file) = &mut self.file {
-            let text = match self.override_format.as_ref() {
-                Some(format) => {
-                    let new_format = Format::<Local>::merged(format, item.format());
+    /// Write a single line to the open file at the time `now`, rotating it first if required. If
+    /// the file couldn't be reopened after rotating, opening it is retried first.
+    fn write_line(&mut self, text: &str, now: DateTime<Tz>) {
+        if self.path.is_none() {
+            return;
+        }
 
-                    new_format.build_string(item.level(), &item.into_message())
-                }
-                None => item.into(),
-            };
+        let len = text.len() as u64 + 1;
+        let res = self.rotate_if_needed(len, &now);
 
+        if self.panic_on_fail {
+            res.unwrap();
+        }
+
+        if let Some(file) = &mut self.file {
             let res = writeln!(file, "{}", text);
 
+            match &res {
+                Ok(()) => self.file_size += len,
+                // Part of the line may have been written, so use the size of the file instead.
+                Err(_) => {
+                    if let Ok(metadata) = file.metadata() {
+                        self.file_size = metadata.len();
+                    }
+                }
+            }
+
             if self.panic_on_fail {
                 res.unwrap()
             }
@@ -172,19 +358,100 @@ impl Logger for FileLogger<Local> {
             }
         }
     }
+
+    /// Prevent logging any messages with these log levels
+    pub fn restrict_log_levels(&mut self, levels: &[LogLevel]) {
+        self.levels.restrict(levels);
+    }
+
+    /// Allow any previously restricted log level. This does not allow levels below the
+    /// [minimum level](FileLogger::set_min_level).
+    pub fn allow_log_levels(&mut self, levels: &[LogLevel]) {
+        self.levels.allow(levels);
+    }
+
+    /// Prevent logging any messages less severe than `level`. Restricted levels remain restricted
+    /// even if they are above the threshold.
+    pub fn set_min_level(&mut self, level: LogLevel) {
+        self.levels.set_min_level(Some(level));
+    }
+
+    /// Remove the minimum level set by [set_min_level](FileLogger::set_min_level).
+    pub fn clear_min_level(&mut self) {
+        self.levels.set_min_level(None);
+    }
+
+    /// Get the minimum level, if any.
+    pub fn min_level(&self) -> Option<LogLevel> {
+        return self.levels.min_level();
+    }
+
+    /// Use per-module minimum levels, see [Directives]. A directive matching a log's module takes
+    /// precedence over the [minimum level](FileLogger::set_min_level), which remains in use for
+    /// modules no directive matches. Restricted levels remain restricted.
+    pub fn set_directives(&mut self, directives: Directives) {
+        self.levels.set_directives(Some(directives));
+    }
+
+    /// Remove the directives set by [set_directives](FileLogger::set_directives).
+    pub fn clear_directives(&mut self) {
+        self.levels.set_directives(None);
+    }
+
+    /// Get the per-module directives, if any.
+    pub fn directives(&self) -> Option<&Directives> {
+        return self.levels.directives();
+    }
+
+    /// Get a handle to the filter of enabled levels. It is updated whenever the minimum or
+    /// restricted levels change and can be checked without access to the logger. With
+    /// directives set, a level is enabled if any module may log at it.
+    pub fn level_filter(&self) -> LevelFilter {
+        return self.levels.filter();
+    }
+
+    /// Use `filter` as the filter of enabled levels, overwriting its state with this logger's
+    /// levels. This allows a filter to be created before the logger, for example by a global.
+    pub fn set_level_filter(&mut self, filter: LevelFilter) {
+        self.levels.set_filter(filter);
+    }
 }
 
-impl Logger for FileLogger<Utc> {
+impl<Tz: TimeZone> Logger for FileLogger<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
     type ReturnType = ();
 
-    fn can_log_item<Tz: TimeZone>(&self, item: &LogItem<Tz>) -> bool
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return self
+            .levels
+            .is_enabled(metadata.level(), Some(metadata.module_path()));
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        return self.timer.now();
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        return self.timer.clock();
+    }
+
+    fn enabled_levels(&self) -> Option<LevelFilter> {
+        return Some(self.levels.filter());
+    }
+
+    fn can_log_item<T: TimeZone>(&self, item: &LogItem<T>) -> bool
     where
-        Tz::Offset: std::fmt::Display,
-        DateTime<Local>: From<DateTime<Tz>>,
-        DateTime<Utc>: From<DateTime<Tz>>,
-        DateTime<Tz>: Copy,
+        T::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<T>>,
+        DateTime<Utc>: From<DateTime<T>>,
+        DateTime<T>: Copy,
     {
-        return !self.restricted_log_levels.contains(&item.level());
+        return self
+            .levels
+            .is_enabled(item.level(), item.format().module_path_str());
     }
 
     fn log_item<T: TimeZone>(&mut self, item: LogItem<T>) -> Self::ReturnType
@@ -194,46 +461,52 @@ impl Logger for FileLogger<Utc> {
         DateTime<Utc>: From<DateTime<T>>,
         DateTime<T>: Copy,
     {
-        if let Some(file) = &mut self.file {
-            let text = match self.override_format.as_ref() {
-                Some(format) => {
-                    let new_format = Format::<Utc>::merged(format, item.format());
-
-                    new_format.build_string(item.level(), &item.into_message())
-                }
-                None => item.into(),
-            };
-
-            let res = writeln!(file, "{}", text);
-
-            if self.panic_on_fail {
-                res.unwrap()
-            }
-
-            let res = file.flush();
-
-            if self.panic_on_fail {
-                res.unwrap();
-            }
+        if self.path.is_some() {
+            let item = self.timer.record(item.with_timezone(self.timezone.clone()));
+            let mut format = item.format().resolve(self.override_format.as_ref());
+            format.set_colour_mode(ColourMode::Never);
+
+            let mut buffer = std::mem::take(&mut self.buffer);
+            buffer.clear();
+            format.write(self.encoding, &mut buffer, &item.record());
+
+            let now = self.current_time();
+            self.write_line(&buffer, now);
+            self.buffer = buffer;
         }
     }
 }
 
 impl StringLogger<Local> {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
         return Self::new_tz();
     }
 }
 
-impl<Tz: TimeZone> StringLogger<Tz>
+impl<Tz: DefaultTimeZone> StringLogger<Tz>
 where
     Tz::Offset: std::fmt::Display,
     DateTime<Tz>: Copy,
 {
     /// Create a new instance of [StringLogger] with a custom Tz.
     pub fn new_tz() -> Self {
+        return Self::new_in(Tz::default_timezone());
+    }
+}
+
+impl<Tz: TimeZone> StringLogger<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    /// Create a new instance of [StringLogger] that builds logs in `timezone`.
+    pub fn new_in(timezone: Tz) -> Self {
         return Self {
             override_format: None,
+            timezone,
+            timer: Timer::new(system_clock()),
+            encoding: Encoding::Text,
         };
     }
 
@@ -243,34 +516,36 @@ where
     pub fn set_override(&mut self, format: Format<Tz>) {
         self.override_format = Some(format);
     }
-}
 
-impl Logger for StringLogger<Local> {
-    /// [StringLogger] returns the formatted [String] instead of nothing.
-    type ReturnType = String;
-
-    fn log_item<T: TimeZone>(&mut self, item: LogItem<T>) -> Self::ReturnType
-    where
-        T::Offset: std::fmt::Display,
-        DateTime<Local>: From<DateTime<T>>,
-        DateTime<Utc>: From<DateTime<T>>,
-        DateTime<T>: Copy,
-    {
-        return match self.override_format.as_ref() {
-            Some(format) => {
-                let new_format = Format::<Local>::merged(format, item.format());
+    /// Set the [Encoding] used to build each log. Using [Encoding::Json] returns one JSON object
+    /// per log, the location metadata is still taken from the merged override format.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
 
-                new_format.build_string(item.level(), &item.into_message())
-            }
-            None => item.into(),
-        };
+    /// Use `clock` instead of the [SystemClock](crate::clock::SystemClock) to obtain the time. The
+    /// time [elapsed](crate::format::FormatItem::Elapsed) is measured from when the clock is set.
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        self.timer.set_clock(clock);
     }
 }
 
-impl Logger for StringLogger<Utc> {
+impl<Tz: TimeZone> Logger for StringLogger<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
     /// [StringLogger] returns the formatted [String] instead of nothing.
     type ReturnType = String;
 
+    fn now(&self) -> DateTime<Utc> {
+        return self.timer.now();
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        return self.timer.clock();
+    }
+
     fn log_item<T: TimeZone>(&mut self, item: LogItem<T>) -> Self::ReturnType
     where
         T::Offset: std::fmt::Display,
@@ -278,13 +553,15 @@ impl Logger for StringLogger<Utc> {
         DateTime<Utc>: From<DateTime<T>>,
         DateTime<T>: Copy,
     {
-        return match self.override_format.as_ref() {
-            Some(format) => {
-                let new_format = Format::<Utc>::merged(format, item.format());
+        let item = self.timer.record(item.with_timezone(self.timezone.clone()));
+        let mut output = String::new();
 
-                new_format.build_string(item.level(), &item.into_message())
-            }
-            None => item.into(),
-        };
+        item.format().resolve(self.override_format.as_ref()).write(
+            self.encoding,
+            &mut output,
+            &item.record(),
+        );
+
+        return output;
     }
 }
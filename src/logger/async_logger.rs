@@ -0,0 +1,291 @@
+use crate::clock::{Clock, SharedClock};
+use crate::log::{LevelFilter, LogItem, Logger, Metadata};
+use chrono::{DateTime, FixedOffset, Local, TimeZone, Utc};
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::{self, JoinHandle};
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// What an [AsyncLogger] does with a new log when its queue is full. Every log that is discarded is
+/// counted, see [dropped](AsyncLogger::dropped).
+pub enum OverflowPolicy {
+    /// Wait for the writer thread to make space in the queue, no logs are discarded.
+    Block,
+    /// Discard the new log.
+    DropNewest,
+    /// Discard the oldest log in the queue to make space for the new log.
+    DropOldest,
+}
+
+/// The state shared between an [AsyncLogger] and its writer thread.
+struct Shared {
+    queue: Mutex<Queue>,
+    /// Notified when a log is added to the queue or the logger is closed.
+    added: Condvar,
+    /// Notified when a log is removed from the queue or has been written.
+    removed: Condvar,
+}
+
+struct Queue {
+    items: VecDeque<LogItem<FixedOffset>>,
+    /// True whilst the writer thread is passing a log to the wrapped logger.
+    writing: bool,
+    /// True once the logger is being dropped or the writer thread has stopped.
+    closed: bool,
+    dropped: u64,
+}
+
+/// Wraps any [Logger], passing each log to a dedicated writer thread over a bounded queue so that
+/// logging never blocks on the wrapped logger, for example on disk I/O.
+///
+/// Logs are converted to a fixed offset timezone before being queued, and the wrapped logger's
+/// [can_log_item](Logger::can_log_item) is checked on the writer thread. Levels disabled by the
+/// wrapped logger's [enabled_levels](Logger::enabled_levels) are rejected before being queued.
+/// Logs created by the macros are timestamped with the wrapped logger's [clock](Logger::clock)
+/// when they are queued. Dropping the [AsyncLogger] writes any queued logs before stopping the
+/// writer thread.
+///
+/// ## Using AsyncLogger
+/// ```no_run
+/// use muxide_logging::info;
+/// use muxide_logging::logger::{AsyncLogger, FileLogger, OverflowPolicy};
+/// use chrono::Local;
+///
+/// let mut file_logger = FileLogger::<Local>::new();
+/// file_logger.open_file("file_name").unwrap();
+///
+/// let mut logger = AsyncLogger::new(file_logger, 1024, OverflowPolicy::DropOldest);
+/// info!(logger: logger, "Log message");
+///
+/// // Wait for the message to be written.
+/// logger.flush();
+/// ```
+pub struct AsyncLogger<L: Logger + Send + 'static> {
+    shared: Arc<Shared>,
+    capacity: usize,
+    policy: OverflowPolicy,
+    worker: Option<JoinHandle<L>>,
+    /// The wrapped logger's source of the current time, used to timestamp logs.
+    clock: SharedClock,
+    /// The levels enabled by the wrapped logger, if it has a filter.
+    levels: Option<LevelFilter>,
+}
+
+impl<L: Logger + Send + 'static> AsyncLogger<L> {
+    /// Create a new [AsyncLogger] that passes logs to `logger` on a new thread, queueing at most
+    /// `capacity` logs.
+    pub fn new(logger: L, capacity: usize, policy: OverflowPolicy) -> Self {
+        let levels = logger.enabled_levels();
+        let clock = logger.clock();
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                items: VecDeque::with_capacity(capacity),
+                writing: false,
+                closed: false,
+                dropped: 0,
+            }),
+            added: Condvar::new(),
+            removed: Condvar::new(),
+        });
+
+        let worker_shared = shared.clone();
+        let worker = thread::Builder::new()
+            .name("muxide-logging".to_string())
+            .spawn(move || run(logger, &worker_shared))
+            .expect("Failed to spawn the logging thread.");
+
+        return Self {
+            shared,
+            capacity: capacity.max(1),
+            policy,
+            worker: Some(worker),
+            clock,
+            levels,
+        };
+    }
+
+    /// Block until every queued log has been passed to the wrapped logger.
+    pub fn flush(&self) {
+        let mut queue = self.shared.lock();
+
+        while (!queue.items.is_empty() || queue.writing) && !queue.closed {
+            queue = self.shared.wait(&self.shared.removed, queue);
+        }
+    }
+
+    /// The number of logs that have been discarded because the queue was full or the writer
+    /// thread had stopped.
+    pub fn dropped(&self) -> u64 {
+        return self.shared.lock().dropped;
+    }
+
+    /// The maximum number of queued logs.
+    pub fn capacity(&self) -> usize {
+        return self.capacity;
+    }
+
+    /// The policy used when the queue is full.
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        return self.policy;
+    }
+
+    /// Write any queued logs, stop the writer thread and return the wrapped logger. Returns
+    /// [None] if the wrapped logger panicked.
+    pub fn into_inner(mut self) -> Option<L> {
+        return self.shutdown();
+    }
+
+    fn shutdown(&mut self) -> Option<L> {
+        let worker = self.worker.take()?;
+
+        self.shared.lock().closed = true;
+        self.shared.added.notify_all();
+
+        return worker.join().ok();
+    }
+}
+
+impl<L: Logger + Send + 'static> Drop for AsyncLogger<L> {
+    fn drop(&mut self) {
+        let _ = self.shutdown();
+    }
+}
+
+impl<L: Logger + Send + 'static> Logger for AsyncLogger<L> {
+    /// The wrapped logger's return value is discarded on the writer thread.
+    type ReturnType = ();
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return match self.levels.as_ref() {
+            Some(levels) => levels.is_enabled(metadata.level()),
+            None => true,
+        };
+    }
+
+    fn can_log_item<Tz: TimeZone>(&self, item: &LogItem<Tz>) -> bool
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        return match self.levels.as_ref() {
+            Some(levels) => levels.is_enabled(item.level()),
+            None => true,
+        };
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        return self.clock.now();
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        return self.clock.clone();
+    }
+
+    fn enabled_levels(&self) -> Option<LevelFilter> {
+        return self.levels.clone();
+    }
+
+    fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        let item = item.into_fixed_offset();
+        let mut queue = self.shared.lock();
+
+        loop {
+            if queue.closed {
+                queue.dropped += 1;
+
+                return;
+            }
+
+            if queue.items.len() < self.capacity {
+                queue.items.push_back(item);
+                drop(queue);
+                self.shared.added.notify_one();
+
+                return;
+            }
+
+            match self.policy {
+                OverflowPolicy::Block => queue = self.shared.wait(&self.shared.removed, queue),
+                OverflowPolicy::DropNewest => {
+                    queue.dropped += 1;
+
+                    return;
+                }
+                OverflowPolicy::DropOldest => {
+                    let _ = queue.items.pop_front();
+                    queue.dropped += 1;
+                }
+            }
+        }
+    }
+}
+
+impl Shared {
+    /// Lock the queue, a panic in the wrapped logger never leaves the queue in an invalid state so
+    /// poisoning is ignored.
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        return self.queue.lock().unwrap_or_else(|e| e.into_inner());
+    }
+
+    fn wait<'a>(&self, condvar: &Condvar, guard: MutexGuard<'a, Queue>) -> MutexGuard<'a, Queue> {
+        return condvar.wait(guard).unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+/// Closes the queue when the writer thread stops, including when the wrapped logger panics, so
+/// that blocked loggers and flushes are released.
+struct CloseOnExit<'a>(&'a Shared);
+
+impl Drop for CloseOnExit<'_> {
+    fn drop(&mut self) {
+        let mut queue = self.0.lock();
+
+        queue.closed = true;
+        queue.writing = false;
+        queue.dropped += queue.items.len() as u64;
+        queue.items.clear();
+        drop(queue);
+
+        self.0.removed.notify_all();
+    }
+}
+
+/// The body of the writer thread. Logs are written until the queue is both closed and empty.
+fn run<L: Logger>(mut logger: L, shared: &Shared) -> L {
+    let _close = CloseOnExit(shared);
+
+    loop {
+        let mut queue = shared.lock();
+
+        let item = loop {
+            if let Some(item) = queue.items.pop_front() {
+                queue.writing = true;
+                break item;
+            }
+
+            if queue.closed {
+                return logger;
+            }
+
+            queue = shared.wait(&shared.added, queue);
+        };
+
+        drop(queue);
+        shared.removed.notify_all();
+
+        if logger.can_log_item(&item) {
+            let _ = logger.log_item(item);
+        }
+
+        shared.lock().writing = false;
+        shared.removed.notify_all();
+    }
+}
@@ -0,0 +1,315 @@
+use super::levels::LevelSettings;
+use super::timer::Timer;
+use crate::clock::{system_clock, Clock};
+use crate::directive::Directives;
+use crate::format::{Encoding, Format};
+use crate::log::{LevelFilter, LogItem, LogLevel, Logger, Metadata};
+use crate::style::{no_color, ColourMode};
+use crate::timezone::DefaultTimeZone;
+use chrono::{DateTime, Local, TimeZone, Utc};
+use std::io::{self, IsTerminal, Write};
+use std::sync::Arc;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// A standard stream that a [ConsoleLogger] writes to.
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Clone, Debug)]
+/// A logger that writes each log as a line to the standard output or standard error, useful
+/// whilst developing to see logs in a terminal.
+///
+/// By default [Critical](LogLevel::Critical), [Error](LogLevel::Error) and
+/// [Warning](LogLevel::Warning) logs are written to stderr and all other levels to stdout, this
+/// can be changed with [set_stream](ConsoleLogger::set_stream).
+///
+/// Styling items in formats produce escape codes only when the stream is a terminal and the
+/// `NO_COLOR` environment variable is not set, see [set_colour_mode](ConsoleLogger::set_colour_mode).
+///
+/// ## Using ConsoleLogger
+/// ```
+/// use muxide_logging::{error, info};
+/// use muxide_logging::logger::{ConsoleLogger, Stream};
+/// use muxide_logging::log::LogLevel;
+/// use chrono::Local;
+///
+/// let mut logger = ConsoleLogger::<Local>::new();
+/// logger.set_stream(&[LogLevel::Warning], Stream::Stdout);
+///
+/// info!(logger: logger, "Written to stdout");
+/// error!(logger: logger, "Written to stderr");
+/// ```
+pub struct ConsoleLogger<Tz: TimeZone>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    /// Levels written to stderr, all others are written to stdout.
+    stderr_levels: Vec<LogLevel>,
+    /// Whether each stream was a terminal when the logger was created.
+    stdout_is_terminal: bool,
+    stderr_is_terminal: bool,
+    /// Whether styling items produce escape codes.
+    colour_mode: ColourMode,
+    /// Whether we should panic on IO errors or ignore them.
+    panic_on_fail: bool,
+    /// A custom Format to use as an override.
+    override_format: Option<Format<Tz>>,
+    /// The timezone logs are written in.
+    timezone: Tz,
+    /// The source of the current time, used to timestamp logs.
+    timer: Timer,
+    /// The restricted levels, minimum level and directives deciding which logs are written.
+    levels: LevelSettings,
+    /// How each log is encoded before being written.
+    encoding: Encoding,
+    /// Reused to build each line so that logging doesn't allocate.
+    buffer: String,
+}
+
+impl Stream {
+    /// Returns true if the stream is currently connected to a terminal.
+    pub fn is_terminal(self) -> bool {
+        return match self {
+            Stream::Stdout => io::stdout().is_terminal(),
+            Stream::Stderr => io::stderr().is_terminal(),
+        };
+    }
+}
+
+impl<Tz: DefaultTimeZone> ConsoleLogger<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    /// Create a new instance of [ConsoleLogger].
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> ConsoleLogger<Tz> {
+        return Self::new_in(Tz::default_timezone());
+    }
+}
+
+impl<Tz: TimeZone> ConsoleLogger<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    /// Create a new instance of [ConsoleLogger] that writes logs in `timezone`.
+    pub fn new_in(timezone: Tz) -> ConsoleLogger<Tz> {
+        return Self {
+            stderr_levels: vec![LogLevel::Critical, LogLevel::Error, LogLevel::Warning],
+            stdout_is_terminal: Stream::Stdout.is_terminal(),
+            stderr_is_terminal: Stream::Stderr.is_terminal(),
+            colour_mode: ColourMode::Auto,
+            panic_on_fail: false,
+            override_format: None,
+            timezone,
+            timer: Timer::new(system_clock()),
+            levels: LevelSettings::new(),
+            encoding: Encoding::Text,
+            buffer: String::new(),
+        };
+    }
+
+    /// Write logs with these levels to `stream`.
+    pub fn set_stream(&mut self, levels: &[LogLevel], stream: Stream) {
+        for level in levels {
+            let idx = self.stderr_levels.iter().position(|l| level == l);
+
+            match (stream, idx) {
+                (Stream::Stderr, None) => self.stderr_levels.push(*level),
+                (Stream::Stdout, Some(idx)) => {
+                    self.stderr_levels.remove(idx);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Get the stream logs with `level` are written to.
+    pub fn stream(&self, level: LogLevel) -> Stream {
+        if self.stderr_levels.contains(&level) {
+            return Stream::Stderr;
+        }
+
+        return Stream::Stdout;
+    }
+
+    /// Returns true if `stream` was connected to a terminal when the logger was created.
+    pub fn is_terminal(&self, stream: Stream) -> bool {
+        return match stream {
+            Stream::Stdout => self.stdout_is_terminal,
+            Stream::Stderr => self.stderr_is_terminal,
+        };
+    }
+
+    /// Sets whether a failed write to a stream should result in a panic. By default this behaviour
+    /// is disabled.
+    pub fn set_panic_on_fail(&mut self, b: bool) {
+        self.panic_on_fail = b;
+    }
+
+    /// Override any format supplied to the [log_item](Logger::log_item) method. This format is not used instead of
+    /// the one supplied instead it is merged, selecting any values that are set but preferring
+    /// values from the overridden format.
+    pub fn set_override(&mut self, format: Format<Tz>) {
+        self.override_format = Some(format);
+    }
+
+    /// Set the [Encoding] used to build each log before it is written.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        self.encoding = encoding;
+    }
+
+    /// Use `clock` instead of the [SystemClock](crate::clock::SystemClock) to obtain the time. The
+    /// time [elapsed](crate::format::FormatItem::Elapsed) is measured from when the clock is set.
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        self.timer.set_clock(clock);
+    }
+
+    /// Prevent logging any messages with these log levels
+    pub fn restrict_log_levels(&mut self, levels: &[LogLevel]) {
+        self.levels.restrict(levels);
+    }
+
+    /// Allow any previously restricted log level. This does not allow levels below the
+    /// [minimum level](ConsoleLogger::set_min_level).
+    pub fn allow_log_levels(&mut self, levels: &[LogLevel]) {
+        self.levels.allow(levels);
+    }
+
+    /// Prevent logging any messages less severe than `level`. Restricted levels remain restricted
+    /// even if they are above the threshold.
+    pub fn set_min_level(&mut self, level: LogLevel) {
+        self.levels.set_min_level(Some(level));
+    }
+
+    /// Remove the minimum level set by [set_min_level](ConsoleLogger::set_min_level).
+    pub fn clear_min_level(&mut self) {
+        self.levels.set_min_level(None);
+    }
+
+    /// Get the minimum level, if any.
+    pub fn min_level(&self) -> Option<LogLevel> {
+        return self.levels.min_level();
+    }
+
+    /// Use per-module minimum levels, see [Directives] and
+    /// [FileLogger::set_directives](super::FileLogger::set_directives).
+    pub fn set_directives(&mut self, directives: Directives) {
+        self.levels.set_directives(Some(directives));
+    }
+
+    /// Remove the directives set by [set_directives](ConsoleLogger::set_directives).
+    pub fn clear_directives(&mut self) {
+        self.levels.set_directives(None);
+    }
+
+    /// Get the per-module directives, if any.
+    pub fn directives(&self) -> Option<&Directives> {
+        return self.levels.directives();
+    }
+
+    /// Get a handle to the filter of enabled levels, see
+    /// [FileLogger::level_filter](super::FileLogger::level_filter).
+    pub fn level_filter(&self) -> LevelFilter {
+        return self.levels.filter();
+    }
+
+    /// Set whether styling items produce escape codes. This takes precedence over the colour mode
+    /// of the formats, by default [Auto](ColourMode::Auto).
+    pub fn set_colour_mode(&mut self, mode: ColourMode) {
+        self.colour_mode = mode;
+    }
+
+    /// Get whether styling items produce escape codes.
+    pub fn colour_mode(&self) -> ColourMode {
+        return self.colour_mode;
+    }
+
+    /// Decide whether a log written to `stream` is coloured. In [Auto](ColourMode::Auto) mode the
+    /// logger's mode is checked first, then the format's, and finally colour is used if the
+    /// stream is a terminal and `NO_COLOR` is not set.
+    fn resolve_colour_mode(&self, stream: Stream, format_mode: ColourMode) -> ColourMode {
+        return match (self.colour_mode, format_mode) {
+            (ColourMode::Auto, ColourMode::Auto) => {
+                if self.is_terminal(stream) && !no_color() {
+                    ColourMode::Always
+                } else {
+                    ColourMode::Never
+                }
+            }
+            (ColourMode::Auto, mode) | (mode, _) => mode,
+        };
+    }
+
+    /// Write a single line to the stream for `level`.
+    fn write_line(&self, level: LogLevel, text: &str) {
+        let res = match self.stream(level) {
+            Stream::Stdout => writeln!(io::stdout().lock(), "{}", text),
+            Stream::Stderr => writeln!(io::stderr().lock(), "{}", text),
+        };
+
+        if self.panic_on_fail {
+            res.unwrap();
+        }
+    }
+}
+
+impl<Tz: TimeZone> Logger for ConsoleLogger<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    type ReturnType = ();
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return self
+            .levels
+            .is_enabled(metadata.level(), Some(metadata.module_path()));
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        return self.timer.now();
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        return self.timer.clock();
+    }
+
+    fn enabled_levels(&self) -> Option<LevelFilter> {
+        return Some(self.levels.filter());
+    }
+
+    fn can_log_item<T: TimeZone>(&self, item: &LogItem<T>) -> bool
+    where
+        T::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<T>>,
+        DateTime<Utc>: From<DateTime<T>>,
+        DateTime<T>: Copy,
+    {
+        return self
+            .levels
+            .is_enabled(item.level(), item.format().module_path_str());
+    }
+
+    fn log_item<T: TimeZone>(&mut self, item: LogItem<T>) -> Self::ReturnType
+    where
+        T::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<T>>,
+        DateTime<Utc>: From<DateTime<T>>,
+        DateTime<T>: Copy,
+    {
+        let item = self.timer.record(item.with_timezone(self.timezone.clone()));
+        let level = item.level();
+        let stream = self.stream(level);
+        let mut format = item.format().resolve(self.override_format.as_ref());
+        format.set_colour_mode(self.resolve_colour_mode(stream, format.colour_mode()));
+
+        self.buffer.clear();
+        format.write(self.encoding, &mut self.buffer, &item.record());
+        self.write_line(level, &self.buffer);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::directive::Directives;
+use crate::log::{LevelFilter, LogLevel};
+
+#[derive(Clone, Debug)]
+/// The level configuration shared by the built-in loggers: restricted levels, a minimum level and
+/// per-module directives, mirrored into a [LevelFilter] for lock-free checks.
+pub(crate) struct LevelSettings {
+    /// Any logs with these log levels will be ignored.
+    restricted_log_levels: Vec<LogLevel>,
+    /// Any logs less severe than this level will be ignored.
+    min_level: Option<LogLevel>,
+    /// Per-module minimum levels, taking precedence over `min_level` for the modules they match.
+    directives: Option<Directives>,
+    /// The levels allowed by the minimum and restricted levels and the directives of any module,
+    /// kept up to date for lock-free checks.
+    filter: LevelFilter,
+}
+
+impl LevelSettings {
+    pub(crate) fn new() -> Self {
+        return Self {
+            restricted_log_levels: Vec::new(),
+            min_level: None,
+            directives: None,
+            filter: LevelFilter::new(),
+        };
+    }
+
+    pub(crate) fn restrict(&mut self, levels: &[LogLevel]) {
+        for level in levels {
+            if !self.restricted_log_levels.contains(level) {
+                self.restricted_log_levels.push(*level);
+            }
+        }
+
+        self.update_filter();
+    }
+
+    pub(crate) fn allow(&mut self, levels: &[LogLevel]) {
+        for level in levels {
+            if let Some(idx) = self.restricted_log_levels.iter().position(|l| level == l) {
+                self.restricted_log_levels.remove(idx);
+            }
+        }
+
+        self.update_filter();
+    }
+
+    pub(crate) fn set_min_level(&mut self, level: Option<LogLevel>) {
+        self.min_level = level;
+        self.update_filter();
+    }
+
+    pub(crate) fn min_level(&self) -> Option<LogLevel> {
+        return self.min_level;
+    }
+
+    pub(crate) fn set_directives(&mut self, directives: Option<Directives>) {
+        self.directives = directives;
+        self.update_filter();
+    }
+
+    pub(crate) fn directives(&self) -> Option<&Directives> {
+        return self.directives.as_ref();
+    }
+
+    pub(crate) fn filter(&self) -> LevelFilter {
+        return self.filter.clone();
+    }
+
+    /// Use `filter` for lock-free checks, overwriting its state with these settings.
+    pub(crate) fn set_filter(&mut self, filter: LevelFilter) {
+        self.filter = filter;
+        self.update_filter();
+    }
+
+    fn update_filter(&self) {
+        // The lowest threshold of any module, None if some module has no threshold at all.
+        let lowest_level = match self.directives.as_ref() {
+            Some(directives) => match directives.default_level().or(self.min_level) {
+                Some(fallback) => directives
+                    .lowest_level()
+                    .map(|level| level.min(fallback))
+                    .or(Some(fallback)),
+                None => None,
+            },
+            None => self.min_level,
+        };
+
+        self.filter
+            .set_enabled(LogLevel::ALL.iter().copied().filter(|level| {
+                return self.passes(*level, lowest_level);
+            }));
+    }
+
+    /// Returns true if logs at `level` from `module_path` pass the directives, minimum level and
+    /// restricted levels.
+    pub(crate) fn is_enabled(&self, level: LogLevel, module_path: Option<&str>) -> bool {
+        let min_level = self
+            .directives
+            .as_ref()
+            .and_then(|directives| directives.level_for(module_path))
+            .or(self.min_level);
+
+        return self.passes(level, min_level);
+    }
+
+    /// Returns true if `level` is at least `min_level` and not restricted.
+    fn passes(&self, level: LogLevel, min_level: Option<LogLevel>) -> bool {
+        let above_threshold = match min_level {
+            Some(min_level) => level >= min_level,
+            None => true,
+        };
+
+        return above_threshold && !self.restricted_log_levels.contains(&level);
+    }
+}
@@ -0,0 +1,236 @@
+use super::levels::LevelSettings;
+use crate::clock::{system_clock, Clock, SharedClock};
+use crate::directive::Directives;
+use crate::format::Format;
+use crate::log::{DynLogger, LogItem, LogLevel, Logger, Metadata};
+use chrono::{DateTime, FixedOffset, Local, Offset, TimeZone, Utc};
+use std::sync::Arc;
+
+/// A child logger of a [MultiLogger] with its own levels and override format, which are checked
+/// and applied before the child's own.
+pub struct Sink {
+    logger: Box<dyn DynLogger + Send>,
+    /// The restricted levels, minimum level and directives deciding which logs reach the child.
+    levels: LevelSettings,
+    /// A custom Format merged into each log before it is passed to the child.
+    override_format: Option<Box<dyn Override + Send>>,
+}
+
+/// An override format kept in its own timezone, so that it is converted to the offset in effect
+/// at the time of each log rather than the offset when the sink was created.
+trait Override {
+    /// Convert `item` into the offset of the override at the time of the log and merge the
+    /// override into its format, see [Format::merged].
+    fn apply(&self, item: LogItem<FixedOffset>) -> LogItem<FixedOffset>;
+}
+
+/// A logger that passes each log to several child loggers, for example writing errors to both a
+/// file and the console whilst only writing less severe logs to the file.
+///
+/// Each [Sink] is checked in the order it was added, a log is cloned for every sink that accepts
+/// it. Logs are converted to a fixed offset timezone before being passed on, and logs created by
+/// the macros are timestamped with the [MultiLogger]'s clock rather than the clocks of the sinks.
+///
+/// ## Using MultiLogger
+/// ```no_run
+/// use muxide_logging::{error, info};
+/// use muxide_logging::log::LogLevel;
+/// use muxide_logging::logger::{ConsoleLogger, FileLogger, MultiLogger, Sink};
+/// use chrono::Local;
+///
+/// let mut file_logger = FileLogger::<Local>::new();
+/// file_logger.open_file("file_name").unwrap();
+///
+/// let mut logger = MultiLogger::new()
+///     .with(Sink::new(file_logger))
+///     .with(Sink::new(ConsoleLogger::<Local>::new()).with_min_level(LogLevel::Error));
+///
+/// info!(logger: logger, "Only written to the file");
+/// error!(logger: logger, "Written to the file and the console");
+/// ```
+pub struct MultiLogger {
+    sinks: Vec<Sink>,
+    /// The source of the current time, used to timestamp logs.
+    clock: SharedClock,
+}
+
+impl Sink {
+    /// Create a new [Sink] passing every log to `logger`.
+    pub fn new<L: Logger + Send + 'static>(logger: L) -> Self {
+        return Self::from_box(Box::new(logger));
+    }
+
+    /// Create a new [Sink] from an already boxed logger.
+    pub fn from_box(logger: Box<dyn DynLogger + Send>) -> Self {
+        return Self {
+            logger,
+            levels: LevelSettings::new(),
+            override_format: None,
+        };
+    }
+
+    /// Don't pass logs with these levels to the child.
+    pub fn with_restricted_levels(mut self, levels: &[LogLevel]) -> Self {
+        self.levels.restrict(levels);
+
+        return self;
+    }
+
+    /// Don't pass logs less severe than `level` to the child.
+    pub fn with_min_level(mut self, level: LogLevel) -> Self {
+        self.levels.set_min_level(Some(level));
+
+        return self;
+    }
+
+    /// Use per-module minimum levels, see [Directives].
+    pub fn with_directives(mut self, directives: Directives) -> Self {
+        self.levels.set_directives(Some(directives));
+
+        return self;
+    }
+
+    /// Merge `format` into each log passed to the child, preferring values from `format`. See
+    /// [Format::merged]. The log is converted into the timezone of `format` using the offset at
+    /// the time of the log, so it stays correct when the offset changes, such as for daylight
+    /// saving.
+    pub fn with_override<Tz: TimeZone + Send + 'static>(mut self, format: Format<Tz>) -> Self
+    where
+        Tz::Offset: std::fmt::Display + Send,
+        DateTime<Tz>: Copy,
+    {
+        self.override_format = Some(Box::new(format));
+
+        return self;
+    }
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return self
+            .levels
+            .is_enabled(metadata.level(), Some(metadata.module_path()))
+            && self.logger.enabled_dyn(metadata);
+    }
+
+    fn accepts(&self, level: LogLevel, module_path: Option<&str>) -> bool {
+        return self.levels.is_enabled(level, module_path);
+    }
+
+    fn log(&mut self, item: LogItem<FixedOffset>) {
+        let item = match self.override_format.as_ref() {
+            Some(format) => format.apply(item),
+            None => item,
+        };
+
+        if self.logger.can_log_dyn(&item) {
+            self.logger.log_dyn(item);
+        }
+    }
+}
+
+impl<Tz: TimeZone> Override for Format<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+    DateTime<Tz>: Copy,
+{
+    fn apply(&self, item: LogItem<FixedOffset>) -> LogItem<FixedOffset> {
+        let offset = self
+            .constant_time()
+            .unwrap_or_else(|| item.timestamp().with_timezone(self.timezone()))
+            .offset()
+            .fix();
+        let item = item.with_timezone::<FixedOffset>(offset);
+        let merged = Format::<FixedOffset>::merged::<FixedOffset>(
+            &self.to_timezone::<FixedOffset>(offset),
+            item.format(),
+        );
+
+        return item.with_format(merged);
+    }
+}
+
+impl MultiLogger {
+    /// Create a new [MultiLogger] without any sinks.
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        return Self {
+            sinks: Vec::new(),
+            clock: system_clock(),
+        };
+    }
+
+    /// Add a sink, returning the logger.
+    pub fn with(mut self, sink: Sink) -> Self {
+        self.add(sink);
+
+        return self;
+    }
+
+    /// Add a sink.
+    pub fn add(&mut self, sink: Sink) {
+        self.sinks.push(sink);
+    }
+
+    /// Get the number of sinks.
+    pub fn len(&self) -> usize {
+        return self.sinks.len();
+    }
+
+    /// Returns true if there are no sinks.
+    pub fn is_empty(&self) -> bool {
+        return self.sinks.is_empty();
+    }
+
+    /// Use `clock` to obtain the current time, by default the
+    /// [SystemClock](crate::clock::SystemClock).
+    pub fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        self.clock = Arc::new(clock);
+    }
+}
+
+impl Logger for MultiLogger {
+    type ReturnType = ();
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return self.sinks.iter().any(|sink| sink.enabled(metadata));
+    }
+
+    fn now(&self) -> DateTime<Utc> {
+        return self.clock.now();
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        return self.clock.clone();
+    }
+
+    fn can_log_item<Tz: TimeZone>(&self, item: &LogItem<Tz>) -> bool
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        let module_path = item.format().module_path_str();
+
+        return self
+            .sinks
+            .iter()
+            .any(|sink| sink.accepts(item.level(), module_path));
+    }
+
+    fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        let item = item.into_fixed_offset();
+        let module_path = item.format().module_path_str();
+
+        for sink in self.sinks.iter_mut() {
+            if sink.accepts(item.level(), module_path) {
+                sink.log(item.clone());
+            }
+        }
+    }
+}
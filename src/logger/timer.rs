@@ -0,0 +1,58 @@
+use crate::clock::{Clock, SharedClock};
+use crate::log::LogItem;
+use chrono::{DateTime, TimeZone, Utc};
+use std::sync::Arc;
+
+#[derive(Clone, Debug)]
+/// The clock of a built-in logger along with the times needed for the
+/// [Elapsed](crate::format::FormatItem::Elapsed) and
+/// [SinceLast](crate::format::FormatItem::SinceLast) items.
+pub(crate) struct Timer {
+    clock: SharedClock,
+    /// When the logger started, or was last given a clock.
+    start: DateTime<Utc>,
+    /// The creation time of the previous log written by the logger.
+    last: Option<DateTime<Utc>>,
+}
+
+impl Timer {
+    pub(crate) fn new(clock: SharedClock) -> Self {
+        return Self {
+            start: clock.now(),
+            clock,
+            last: None,
+        };
+    }
+
+    pub(crate) fn now(&self) -> DateTime<Utc> {
+        return self.clock.now();
+    }
+
+    pub(crate) fn clock(&self) -> SharedClock {
+        return self.clock.clone();
+    }
+
+    /// Use `clock`, restarting the timer at its current time.
+    pub(crate) fn set_clock<C: Clock + 'static>(&mut self, clock: C) {
+        *self = Self::new(Arc::new(clock));
+    }
+
+    /// Set the elapsed time and the time since the previous log of an item that is about to be
+    /// written, and remember it as the previous log.
+    pub(crate) fn record<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> LogItem<Tz>
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Tz>: Copy,
+    {
+        let timestamp = item.timestamp().with_timezone(&Utc);
+        let mut item = item.with_elapsed(timestamp - self.start);
+
+        if let Some(last) = self.last {
+            item = item.with_since_last(timestamp - last);
+        }
+
+        self.last = Some(timestamp);
+
+        return item;
+    }
+}
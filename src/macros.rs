@@ -1,133 +1,379 @@
 use crate::format::Format;
-use crate::log::{LogItem, LogLevel, Logger};
+use crate::log::{Fields, LogItem, LogLevel, Logger, Metadata};
 use crate::DefaultLogger;
 use chrono::{DateTime, Local, TimeZone, Utc};
+use std::borrow::Cow;
+use std::fmt;
 use std::ops::DerefMut;
 
 #[macro_export]
 /// Logs a message at the error log level.
 ///
+/// The message is either a format string followed by its arguments, as with [format!], or an
+/// expression that implements `AsRef<str>`. Any `key = value` pairs after the message are added as
+/// structured fields, so named format arguments are rejected but inline arguments such as `{id}`
+/// are supported. The message is only built if the logger is
+/// [enabled](crate::log::Logger::enabled) for the level.
+///
 /// # Usage
 /// Basic usage
 /// ```no_run
 /// use muxide_logging::error;
 ///
+/// let id = 3;
 /// error!("my error message");
+/// error!("pane {} closed", id);
+/// error!("pane {id} closed");
+/// ```
+///
+/// With structured fields
+/// ```no_run
+/// use muxide_logging::error;
+///
+/// let pane = 3;
+/// error!("my error message", pane = pane, cols = 80);
+/// ```
+///
+/// A field named by the format string is rejected, rather than used as a named format argument
+/// ```compile_fail
+/// use muxide_logging::error;
+///
+/// let pane = 3;
+/// error!("pane {pane} closed", pane = pane);
 /// ```
 ///
 /// With a custom logger
 /// ```ignore
 /// use muxide_logging::error;
 ///
-/// error!("my error message", my_logger)
+/// error!(logger: my_logger, "pane {} closed", id);
+/// // A message that isn't a string literal can also be followed by the logger.
+/// error!(message, my_logger);
 /// ```
+///
+/// A logger following a string literal message, `error!("my message", my_logger)`, is now read as
+/// a format argument, see the [migration note](crate#migrating-from-01).
 macro_rules! error {
-    ($message:expr) => {
-        $crate::log_message!($crate::log::LogLevel::Error, $message)
+    (logger: $logger:expr, $($arg:tt)+) => {
+        $crate::__log!(@target [$logger] $crate::log::LogLevel::Error; $($arg)+)
     };
 
-    ($message:expr, $logger:expr) => {
-        $crate::log_message!($crate::log::LogLevel::Error, $message, $logger)
+    ($($arg:tt)+) => {
+        $crate::__log!(@target [] $crate::log::LogLevel::Error; $($arg)+)
     };
 }
 
 #[macro_export]
 /// Logs a message at the warning log level.
 ///
+/// The message is either a format string followed by its arguments, as with [format!], or an
+/// expression that implements `AsRef<str>`. Any `key = value` pairs after the message are added as
+/// structured fields, so named format arguments are rejected but inline arguments such as `{id}`
+/// are supported. The message is only built if the logger is
+/// [enabled](crate::log::Logger::enabled) for the level.
+///
 /// # Usage
 /// Basic usage
 /// ```no_run
 /// use muxide_logging::warning;
 ///
+/// let id = 3;
 /// warning!("my warning message");
+/// warning!("pane {} closed", id);
+/// warning!("pane {id} closed");
+/// ```
+///
+/// With structured fields
+/// ```no_run
+/// use muxide_logging::warning;
+///
+/// let pane = 3;
+/// warning!("my warning message", pane = pane, cols = 80);
 /// ```
 ///
 /// With a custom logger
 /// ```ignore
 /// use muxide_logging::warning;
 ///
-/// warning!("my warning message", my_logger)
+/// warning!(logger: my_logger, "pane {} closed", id);
+/// // A message that isn't a string literal can also be followed by the logger.
+/// warning!(message, my_logger);
 /// ```
+///
+/// A logger following a string literal message, `warning!("my message", my_logger)`, is now read as
+/// a format argument, see the [migration note](crate#migrating-from-01).
 macro_rules! warning {
-    ($message:expr) => {
-        $crate::log_message!($crate::log::LogLevel::Warning, $message)
+    (logger: $logger:expr, $($arg:tt)+) => {
+        $crate::__log!(@target [$logger] $crate::log::LogLevel::Warning; $($arg)+)
     };
 
-    ($message:expr, $logger:expr) => {
-        $crate::log_message!($crate::log::LogLevel::Warning, $message, $logger)
+    ($($arg:tt)+) => {
+        $crate::__log!(@target [] $crate::log::LogLevel::Warning; $($arg)+)
     };
 }
 
 #[macro_export]
 /// Logs a message at the state change log level.
 ///
+/// The message is either a format string followed by its arguments, as with [format!], or an
+/// expression that implements `AsRef<str>`. Any `key = value` pairs after the message are added as
+/// structured fields, so named format arguments are rejected but inline arguments such as `{id}`
+/// are supported. The message is only built if the logger is
+/// [enabled](crate::log::Logger::enabled) for the level.
+///
 /// # Usage
 /// Basic usage
 /// ```no_run
 /// use muxide_logging::state_change;
 ///
-/// state_change!("my error message");
+/// let id = 3;
+/// state_change!("my state change message");
+/// state_change!("pane {} closed", id);
+/// state_change!("pane {id} closed");
+/// ```
+///
+/// With structured fields
+/// ```no_run
+/// use muxide_logging::state_change;
+///
+/// let pane = 3;
+/// state_change!("my state change message", pane = pane, cols = 80);
 /// ```
 ///
 /// With a custom logger
 /// ```ignore
 /// use muxide_logging::state_change;
 ///
-/// state_change!("my error message", my_logger)
+/// state_change!(logger: my_logger, "pane {} closed", id);
+/// // A message that isn't a string literal can also be followed by the logger.
+/// state_change!(message, my_logger);
 /// ```
+///
+/// A logger following a string literal message, `state_change!("my message", my_logger)`, is now read as
+/// a format argument, see the [migration note](crate#migrating-from-01).
 macro_rules! state_change {
-    ($message:expr) => {
-        $crate::log_message!($crate::log::LogLevel::StateChange, $message)
+    (logger: $logger:expr, $($arg:tt)+) => {
+        $crate::__log!(@target [$logger] $crate::log::LogLevel::StateChange; $($arg)+)
     };
 
-    ($message:expr, $logger:expr) => {
-        $crate::log_message!($crate::log::LogLevel::StateChange, $message, $logger)
+    ($($arg:tt)+) => {
+        $crate::__log!(@target [] $crate::log::LogLevel::StateChange; $($arg)+)
     };
 }
 
 #[macro_export]
 /// Logs a message at the information log level.
 ///
+/// The message is either a format string followed by its arguments, as with [format!], or an
+/// expression that implements `AsRef<str>`. Any `key = value` pairs after the message are added as
+/// structured fields, so named format arguments are rejected but inline arguments such as `{id}`
+/// are supported. The message is only built if the logger is
+/// [enabled](crate::log::Logger::enabled) for the level.
+///
 /// # Usage
 /// Basic usage
 /// ```no_run
 /// use muxide_logging::info;
 ///
+/// let id = 3;
 /// info!("my info message");
+/// info!("pane {} closed", id);
+/// info!("pane {id} closed");
+/// ```
+///
+/// With structured fields
+/// ```no_run
+/// use muxide_logging::info;
+///
+/// let pane = 3;
+/// info!("my info message", pane = pane, cols = 80);
 /// ```
 ///
 /// With a custom logger
 /// ```ignore
 /// use muxide_logging::info;
 ///
-/// info!("my info message", my_logger)
+/// info!(logger: my_logger, "pane {} closed", id);
+/// // A message that isn't a string literal can also be followed by the logger.
+/// info!(message, my_logger);
 /// ```
+///
+/// A logger following a string literal message, `info!("my message", my_logger)`, is now read as
+/// a format argument, see the [migration note](crate#migrating-from-01).
 macro_rules! info {
-    ($message:expr) => {
-        $crate::log_message!($crate::log::LogLevel::Information, $message)
+    (logger: $logger:expr, $($arg:tt)+) => {
+        $crate::__log!(@target [$logger] $crate::log::LogLevel::Information; $($arg)+)
+    };
+
+    ($($arg:tt)+) => {
+        $crate::__log!(@target [] $crate::log::LogLevel::Information; $($arg)+)
+    };
+}
+
+#[macro_export]
+/// Logs a message at the trace log level.
+///
+/// The message is either a format string followed by its arguments, as with [format!], or an
+/// expression that implements `AsRef<str>`. Any `key = value` pairs after the message are added as
+/// structured fields, so named format arguments are rejected but inline arguments such as `{id}`
+/// are supported. The message is only built if the logger is
+/// [enabled](crate::log::Logger::enabled) for the level.
+///
+/// # Usage
+/// Basic usage
+/// ```no_run
+/// use muxide_logging::trace;
+///
+/// let id = 3;
+/// trace!("my trace message");
+/// trace!("pane {} closed", id);
+/// trace!("pane {id} closed");
+/// ```
+///
+/// With structured fields
+/// ```no_run
+/// use muxide_logging::trace;
+///
+/// let pane = 3;
+/// trace!("my trace message", pane = pane, cols = 80);
+/// ```
+///
+/// With a custom logger
+/// ```ignore
+/// use muxide_logging::trace;
+///
+/// trace!(logger: my_logger, "pane {} closed", id);
+/// // A message that isn't a string literal can also be followed by the logger.
+/// trace!(message, my_logger);
+/// ```
+///
+/// A logger following a string literal message, `trace!("my message", my_logger)`, is now read as
+/// a format argument, see the [migration note](crate#migrating-from-01).
+macro_rules! trace {
+    (logger: $logger:expr, $($arg:tt)+) => {
+        $crate::__log!(@target [$logger] $crate::log::LogLevel::Trace; $($arg)+)
+    };
+
+    ($($arg:tt)+) => {
+        $crate::__log!(@target [] $crate::log::LogLevel::Trace; $($arg)+)
+    };
+}
+
+#[macro_export]
+/// Logs a message at the debug log level.
+///
+/// The message is either a format string followed by its arguments, as with [format!], or an
+/// expression that implements `AsRef<str>`. Any `key = value` pairs after the message are added as
+/// structured fields, so named format arguments are rejected but inline arguments such as `{id}`
+/// are supported. The message is only built if the logger is
+/// [enabled](crate::log::Logger::enabled) for the level.
+///
+/// # Usage
+/// Basic usage
+/// ```no_run
+/// use muxide_logging::debug;
+///
+/// let id = 3;
+/// debug!("my debug message");
+/// debug!("pane {} closed", id);
+/// debug!("pane {id} closed");
+/// ```
+///
+/// With structured fields
+/// ```no_run
+/// use muxide_logging::debug;
+///
+/// let pane = 3;
+/// debug!("my debug message", pane = pane, cols = 80);
+/// ```
+///
+/// With a custom logger
+/// ```ignore
+/// use muxide_logging::debug;
+///
+/// debug!(logger: my_logger, "pane {} closed", id);
+/// // A message that isn't a string literal can also be followed by the logger.
+/// debug!(message, my_logger);
+/// ```
+///
+/// A logger following a string literal message, `debug!("my message", my_logger)`, is now read as
+/// a format argument, see the [migration note](crate#migrating-from-01).
+macro_rules! debug {
+    (logger: $logger:expr, $($arg:tt)+) => {
+        $crate::__log!(@target [$logger] $crate::log::LogLevel::Debug; $($arg)+)
     };
 
-    ($message:expr, $logger:expr) => {
-        $crate::log_message!($crate::log::LogLevel::Information, $message, $logger)
+    ($($arg:tt)+) => {
+        $crate::__log!(@target [] $crate::log::LogLevel::Debug; $($arg)+)
     };
 }
 
 #[macro_export]
-/// Creates the default [Format] with populated line, column and module_path values based on the
-/// location where this macro was called.
+/// Logs a message at the critical log level.
+///
+/// The message is either a format string followed by its arguments, as with [format!], or an
+/// expression that implements `AsRef<str>`. Any `key = value` pairs after the message are added as
+/// structured fields, so named format arguments are rejected but inline arguments such as `{id}`
+/// are supported. The message is only built if the logger is
+/// [enabled](crate::log::Logger::enabled) for the level.
+///
+/// # Usage
+/// Basic usage
+/// ```no_run
+/// use muxide_logging::critical;
+///
+/// let id = 3;
+/// critical!("my critical message");
+/// critical!("pane {} closed", id);
+/// critical!("pane {id} closed");
+/// ```
+///
+/// With structured fields
+/// ```no_run
+/// use muxide_logging::critical;
+///
+/// let pane = 3;
+/// critical!("my critical message", pane = pane, cols = 80);
+/// ```
+///
+/// With a custom logger
+/// ```ignore
+/// use muxide_logging::critical;
+///
+/// critical!(logger: my_logger, "pane {} closed", id);
+/// // A message that isn't a string literal can also be followed by the logger.
+/// critical!(message, my_logger);
+/// ```
+///
+/// A logger following a string literal message, `critical!("my message", my_logger)`, is now read as
+/// a format argument, see the [migration note](crate#migrating-from-01).
+macro_rules! critical {
+    (logger: $logger:expr, $($arg:tt)+) => {
+        $crate::__log!(@target [$logger] $crate::log::LogLevel::Critical; $($arg)+)
+    };
+
+    ($($arg:tt)+) => {
+        $crate::__log!(@target [] $crate::log::LogLevel::Critical; $($arg)+)
+    };
+}
+
+#[macro_export]
+/// Creates the default [Format] with populated line, column, module_path and thread values based
+/// on the location where this macro was called.
 macro_rules! default_format {
     () => {
         $crate::format::Format::default()
             .set_column(column!() as usize)
             .set_line(line!() as usize)
-            .set_module_path(module_path!())
-            .set_file(file!())
+            .set_static_module_path(module_path!())
+            .set_static_file(file!())
+            .set_current_thread()
     };
 }
 
 #[macro_export]
-/// Creates the default [Format] with populated line, column and module_path values based on the
-/// location where this macro was called for a custom timezone, either specified or otherwise.
+/// Creates the default [Format] with populated line, column, module_path and thread values based
+/// on the location where this macro was called for a custom timezone, either specified or otherwise.
 ///
 /// # Usage
 /// Automatic detection
@@ -152,16 +398,18 @@ macro_rules! default_format_custom_tz {
         $crate::format::Format::default_tz()
             .set_column(column!() as usize)
             .set_line(line!() as usize)
-            .set_module_path(module_path!())
-            .set_file(file!())
+            .set_static_module_path(module_path!())
+            .set_static_file(file!())
+            .set_current_thread()
     };
 
     ($tz:ty) => {
         $crate::format::Format::<$tz>::default_tz()
             .set_column(column!() as usize)
             .set_line(line!() as usize)
-            .set_module_path(module_path!())
-            .set_file(file!())
+            .set_static_module_path(module_path!())
+            .set_static_file(file!())
+            .set_current_thread()
     };
 }
 
@@ -205,11 +453,42 @@ macro_rules! build_format_from_items_tz {
     };
 }
 
+#[macro_export]
+/// Builds a set of structured [Fields](crate::log::Fields) from `key = value` pairs, where each
+/// value can be converted into a [FieldValue](crate::log::FieldValue).
+///
+/// # Usage
+/// ```
+/// use muxide_logging::fields;
+/// use muxide_logging::log::FieldValue;
+///
+/// let fields = fields!(pane = 3, title = "shell", active = true);
+///
+/// assert_eq!(fields.get("title"), Some(&FieldValue::String("shell".to_string())));
+/// assert_eq!(fields.len(), 3);
+/// ```
+macro_rules! fields {
+    ($($key:ident = $value:expr),* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut fields = $crate::log::Fields::new();
+        $(fields.insert(stringify!($key), $value);)*
+        fields
+    }};
+}
+
 #[macro_export]
 /// Helper macro for logging a message to a logger.
 macro_rules! log_message {
     ($log_level:expr, $message:expr, $format:expr, $logger:expr) => {
-        $crate::__log_message($log_level, $message, $format, &mut $logger)
+        $crate::__log_message(
+            $log_level,
+            module_path!(),
+            None,
+            || $crate::__message_string(&$message),
+            || $format,
+            $crate::log::Fields::new,
+            &mut $logger,
+        )
     };
 
     ($log_level:expr, $message:expr, $logger:expr) => {
@@ -217,25 +496,234 @@ macro_rules! log_message {
     };
 
     ($log_level:expr, $message:expr) => {
-        $crate::__default_log_message($log_level, $message, $crate::default_format!())
+        $crate::__default_log_message(
+            $log_level,
+            module_path!(),
+            || $crate::__message_string(&$message),
+            || $crate::default_format!(),
+            $crate::log::Fields::new,
+        )
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+/// Internal macro used by the level macros to split the arguments into the message, format
+/// arguments, structured fields and logger.
+macro_rules! __log {
+    // A format string, the remaining arguments are split into format arguments and fields.
+    (@target $target:tt $level:expr; $fmt:literal $($rest:tt)*) => {
+        $crate::__log!(@munch $target $level; $fmt; []; []; $($rest)*)
+    };
+
+    // Any other message expression, optionally followed by fields.
+    (@target $target:tt $level:expr; $message:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::__log!(
+            @emit $target $level;
+            $crate::__message_string(&$message);
+            [$($key = $value,)*]
+        )
+    };
+
+    // Any other message expression followed by a logger and optionally fields.
+    (@target [] $level:expr; $message:expr, $logger:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::__log!(
+            @emit [$logger] $level;
+            $crate::__message_string(&$message);
+            [$($key = $value,)*]
+        )
+    };
+
+    (@munch $target:tt $level:expr; $fmt:literal; [$($args:tt)*]; [$($fields:tt)*]; $(,)?) => {{
+        $crate::__log!(@named $fmt; $($fields)*);
+        $crate::__log!(
+            @emit $target $level;
+            $crate::__format_message(format_args!($fmt $($args)*));
+            [$($fields)*]
+        )
+    }};
+
+    // Fields named by the format string were meant as named format arguments.
+    (@named $fmt:literal;) => {};
+
+    (@named $fmt:literal; $($key:ident = $value:expr,)+) => {
+        const _: () = $crate::__reject_named_arguments($fmt, &[$(stringify!($key)),+]);
+    };
+
+    (
+        @munch $target:tt $level:expr; $fmt:literal; [$($args:tt)*]; [$($fields:tt)*];
+        , $key:ident = $value:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::__log!(
+            @munch $target $level; $fmt; [$($args)*]; [$($fields)* $key = $value,];
+            $(, $($rest)*)?
+        )
+    };
+
+    (
+        @munch $target:tt $level:expr; $fmt:literal; [$($args:tt)*]; [$($fields:tt)*];
+        , $arg:expr $(, $($rest:tt)*)?
+    ) => {
+        $crate::__log!(
+            @munch $target $level; $fmt; [$($args)* , $arg]; [$($fields)*];
+            $(, $($rest)*)?
+        )
+    };
+
+    (@emit [] $level:expr; $message:expr; [$($fields:tt)*]) => {
+        $crate::__default_log_message(
+            $level,
+            module_path!(),
+            || $message,
+            || $crate::default_format!(),
+            || $crate::fields!($($fields)*),
+        )
+    };
+
+    (@emit [$logger:expr] $level:expr; $message:expr; [$($fields:tt)*]) => {
+        $crate::__log_message(
+            $level,
+            module_path!(),
+            None,
+            || $message,
+            || $crate::default_format!(),
+            || $crate::fields!($($fields)*),
+            &mut $logger,
+        )
     };
 }
 
 #[doc(hidden)]
-/// A wrapper for __log_message that tries to lock the default logger.
-pub fn __default_log_message<S: AsRef<str>, Tz: TimeZone>(
+/// Converts a message expression that isn't a format string into an owned message.
+pub fn __message_string<S: AsRef<str> + ?Sized>(message: &S) -> Cow<'static, str> {
+    return Cow::Owned(message.as_ref().to_string());
+}
+
+#[doc(hidden)]
+/// Fails to compile if the format string `pattern` has a placeholder named after one of the
+/// structured field `keys`, as the macros never take `key = value` pairs as named format
+/// arguments.
+pub const fn __reject_named_arguments(pattern: &str, keys: &[&str]) {
+    let pattern = pattern.as_bytes();
+    let mut idx = 0;
+
+    while idx < pattern.len() {
+        if pattern[idx] != b'{' {
+            idx += 1;
+        } else if idx + 1 < pattern.len() && pattern[idx + 1] == b'{' {
+            idx += 2;
+        } else {
+            let start = idx + 1;
+            let mut end = start;
+
+            while end < pattern.len() && pattern[end] != b'}' && pattern[end] != b':' {
+                end += 1;
+            }
+
+            let mut key = 0;
+
+            while key < keys.len() {
+                if names_key(pattern, start, end, keys[key].as_bytes()) {
+                    panic!(
+                        "named format arguments aren't supported, `key = value` pairs after the \
+                         message are structured fields"
+                    );
+                }
+
+                key += 1;
+            }
+
+            idx = end;
+        }
+    }
+}
+
+/// Returns true if the placeholder name `pattern[start..end]` is `key`.
+const fn names_key(pattern: &[u8], start: usize, end: usize, key: &[u8]) -> bool {
+    if end - start != key.len() {
+        return false;
+    }
+
+    let mut idx = 0;
+
+    while idx < key.len() {
+        if pattern[start + idx] != key[idx] {
+            return false;
+        }
+
+        idx += 1;
+    }
+
+    return true;
+}
+
+#[doc(hidden)]
+/// Formats the message of a format string, borrowing it instead if it has no arguments.
+pub fn __format_message(arguments: fmt::Arguments) -> Cow<'static, str> {
+    return match arguments.as_str() {
+        Some(message) => Cow::Borrowed(message),
+        None => Cow::Owned(fmt::format(arguments)),
+    };
+}
+
+#[doc(hidden)]
+/// A wrapper for __log_message that tries to lock the installed logger, or otherwise the default
+/// logger. Disabled levels are rejected and the log is timestamped before locking, so waiting for
+/// another thread to release the logger doesn't delay the timestamp.
+pub fn __default_log_message<M, F, G, Tz: TimeZone>(
     log_level: LogLevel,
-    message: S,
-    format: Format<Tz>,
+    module_path: &str,
+    message: M,
+    format: F,
+    fields: G,
 ) -> Option<<DefaultLogger as Logger>::ReturnType>
 where
+    M: FnOnce() -> Cow<'static, str>,
+    F: FnOnce() -> Format<Tz>,
+    G: FnOnce() -> Fields,
     Tz::Offset: std::fmt::Display,
     DateTime<Local>: From<DateTime<Tz>>,
     DateTime<Utc>: From<DateTime<Tz>>,
     DateTime<Tz>: Copy,
 {
+    match crate::ENABLED_LEVELS.read() {
+        Ok(levels) if levels.as_ref().is_some_and(|l| !l.is_enabled(log_level)) => return None,
+        Ok(_) => {}
+        Err(_) => return None,
+    }
+
+    let now = match crate::DEFAULT_CLOCK.read() {
+        Ok(clock) => clock.now(),
+        Err(_) => return None,
+    };
+
+    match crate::INSTALLED_LOGGER.lock() {
+        Ok(mut installed) => {
+            if let Some(logger) = installed.as_mut() {
+                return __log_message(
+                    log_level,
+                    module_path,
+                    Some(now),
+                    message,
+                    format,
+                    fields,
+                    logger.as_mut(),
+                );
+            }
+        }
+        Err(_) => return None,
+    }
+
     if let Ok(mut logger) = crate::DEFAULT_LOGGER.lock() {
-        return __log_message(log_level, message, format, logger.deref_mut());
+        return __log_message(
+            log_level,
+            module_path,
+            Some(now),
+            message,
+            format,
+            fields,
+            logger.deref_mut(),
+        );
     } else {
         return None;
     }
@@ -244,19 +732,40 @@ where
 #[doc(hidden)]
 /// Internal method used to write the log message to a file. We need a method instead of including
 /// the macro because 'let' variables are not supported in some of the contexts we wish to support.
-pub fn __log_message<S: AsRef<str>, Tz: TimeZone, L: Logger + Logger<ReturnType = T>, T>(
+///
+/// Nothing is built until the logger's [enabled](Logger::enabled) check passes, after which the
+/// whole item, including its message, is passed to [can_log_item](Logger::can_log_item). The log
+/// is timestamped with `now` if it was taken before the logger was locked, otherwise with the
+/// logger's [now](Logger::now).
+pub fn __log_message<M, F, G, Tz: TimeZone, L: Logger<ReturnType = T> + ?Sized, T>(
     log_level: LogLevel,
-    message: S,
-    format: Format<Tz>,
+    module_path: &str,
+    now: Option<DateTime<Utc>>,
+    message: M,
+    format: F,
+    fields: G,
     logger: &mut L,
 ) -> Option<T>
 where
+    M: FnOnce() -> Cow<'static, str>,
+    F: FnOnce() -> Format<Tz>,
+    G: FnOnce() -> Fields,
     Tz::Offset: std::fmt::Display,
     DateTime<Local>: From<DateTime<Tz>>,
     DateTime<Utc>: From<DateTime<Tz>>,
     DateTime<Tz>: Copy,
 {
-    let item = LogItem::new(format, log_level, message.as_ref());
+    if !logger.enabled(&Metadata::new(log_level, module_path)) {
+        return None;
+    }
+
+    let format = format();
+    let timestamp = now
+        .unwrap_or_else(|| logger.now())
+        .with_timezone(format.timezone());
+    let item = LogItem::new_at(format, log_level, "", timestamp)
+        .with_fields(fields())
+        .with_message(message());
 
     if logger.can_log_item(&item) {
         return Some(logger.log_item(item));
@@ -267,8 +776,12 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::logger::StringLogger;
-    use chrono::{DateTime, Utc};
+    use crate::format::Format;
+    use crate::log::{LogItem, LogLevel, Logger};
+    use crate::logger::{FileLogger, StringLogger};
+    use chrono::{DateTime, Local, TimeZone, Utc};
+    use std::cell::Cell;
+    use std::fmt::{self, Display, Formatter};
 
     #[test]
     fn test_error_macro() {
@@ -278,7 +791,7 @@ mod tests {
                 DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
             )),
         );
-        let content = error!("my message", logger).unwrap();
+        let content = error!(logger: logger, "my message").unwrap();
 
         assert_eq!(
             content,
@@ -298,7 +811,7 @@ mod tests {
                 DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
             )),
         );
-        let content = warning!("my message", logger).unwrap();
+        let content = warning!(logger: logger, "my message").unwrap();
 
         assert_eq!(
             content,
@@ -318,7 +831,7 @@ mod tests {
                 DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
             )),
         );
-        let content = state_change!("my message", logger).unwrap();
+        let content = state_change!(logger: logger, "my message").unwrap();
 
         assert_eq!(
             content,
@@ -338,7 +851,7 @@ mod tests {
                 DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
             )),
         );
-        let content = info!("my message", logger).unwrap();
+        let content = info!(logger: logger, "my message").unwrap();
 
         assert_eq!(
             content,
@@ -349,4 +862,182 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_trace_macro() {
+        let mut logger = StringLogger::new_tz();
+        logger.set_override(
+            default_format_custom_tz!(Utc).set_constant_time(DateTime::from(
+                DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
+            )),
+        );
+        let content = trace!(logger: logger, "my message").unwrap();
+
+        assert_eq!(
+            content,
+            format!(
+                "[10:52:37] ({} {}:13) Trace: my message",
+                module_path!(),
+                line!() - 11,
+            )
+        );
+    }
+
+    #[test]
+    fn test_debug_macro() {
+        let mut logger = StringLogger::new_tz();
+        logger.set_override(
+            default_format_custom_tz!(Utc).set_constant_time(DateTime::from(
+                DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
+            )),
+        );
+        let content = debug!(logger: logger, "my message").unwrap();
+
+        assert_eq!(
+            content,
+            format!(
+                "[10:52:37] ({} {}:13) Debug: my message",
+                module_path!(),
+                line!() - 11,
+            )
+        );
+    }
+
+    #[test]
+    fn test_critical_macro() {
+        let mut logger = StringLogger::new_tz();
+        logger.set_override(
+            default_format_custom_tz!(Utc).set_constant_time(DateTime::from(
+                DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
+            )),
+        );
+        let content = critical!(logger: logger, "my message").unwrap();
+
+        assert_eq!(
+            content,
+            format!(
+                "[10:52:37] ({} {}:13) Critical: my message",
+                module_path!(),
+                line!() - 11,
+            )
+        );
+    }
+
+    #[test]
+    fn test_fields_macro() {
+        let mut logger = StringLogger::new_tz();
+        logger.set_override(
+            Format::<Utc>::parse("{level}: {message} [{field:pane}] {fields}").unwrap(),
+        );
+        let pane = 4;
+        let content = info!(
+            logger: logger,
+            "resized",
+            pane = pane,
+            cols = 80,
+            title = "my shell"
+        )
+        .unwrap();
+
+        assert_eq!(
+            content,
+            "Information: resized [4] cols=80 title=\"my shell\"".to_string()
+        );
+    }
+
+    #[test]
+    fn test_format_arguments() {
+        let mut logger = StringLogger::new_tz();
+        logger.set_override(Format::<Utc>::parse("{message} {fields}").unwrap());
+        let id = 7;
+        let name = "shell";
+
+        assert_eq!(
+            info!(logger: logger, "pane {} ({name}) closed", id, code = 0,).unwrap(),
+            "pane 7 (shell) closed code=0"
+        );
+        assert_eq!(info!(logger: logger, "{}-{}", id, id + 1).unwrap(), "7-8 ");
+        assert_eq!(info!(&format!("pane {}", id), logger).unwrap(), "pane 7 ");
+    }
+
+    #[test]
+    fn test_filtered_message_not_formatted() {
+        struct Counted<'a>(&'a Cell<usize>);
+
+        impl Display for Counted<'_> {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                self.0.set(self.0.get() + 1);
+
+                return f.write_str("counted");
+            }
+        }
+
+        let count = Cell::new(0);
+        let mut filtered = FileLogger::<Utc>::new();
+        filtered.set_min_level(LogLevel::Warning);
+
+        assert_eq!(info!(logger: filtered, "{}", Counted(&count)), None);
+        assert_eq!(count.get(), 0);
+
+        let mut logger = StringLogger::new_tz();
+        logger.set_override(Format::<Utc>::parse("{message}").unwrap());
+
+        assert_eq!(
+            info!(logger: logger, "{}", Counted(&count)).unwrap(),
+            "counted"
+        );
+        assert_eq!(count.get(), 1);
+    }
+
+    #[test]
+    fn test_can_log_item_message() {
+        /// Rejects any log that mentions a secret.
+        struct RedactingLogger {
+            messages: Vec<String>,
+        }
+
+        impl Logger for RedactingLogger {
+            type ReturnType = ();
+
+            fn can_log_item<Tz: TimeZone>(&self, item: &LogItem<Tz>) -> bool
+            where
+                Tz::Offset: std::fmt::Display,
+                DateTime<Local>: From<DateTime<Tz>>,
+                DateTime<Utc>: From<DateTime<Tz>>,
+                DateTime<Tz>: Copy,
+            {
+                return !item.message().contains("secret");
+            }
+
+            fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+            where
+                Tz::Offset: std::fmt::Display,
+                DateTime<Local>: From<DateTime<Tz>>,
+                DateTime<Utc>: From<DateTime<Tz>>,
+                DateTime<Tz>: Copy,
+            {
+                self.messages.push(item.into_message());
+            }
+        }
+
+        let mut logger = RedactingLogger {
+            messages: Vec::new(),
+        };
+
+        assert_eq!(info!(logger: logger, "the {} is 1234", "secret"), None);
+        assert_eq!(info!(logger: logger, "pane {} closed", 3), Some(()));
+        assert_eq!(logger.messages, vec!["pane 3 closed"]);
+    }
+
+    #[test]
+    fn test_reject_named_arguments() {
+        super::__reject_named_arguments("pane {} closed {{pane}} {id:?}", &["pane"]);
+        super::__reject_named_arguments("pane {pane} closed", &[]);
+
+        let named = std::panic::catch_unwind(|| {
+            super::__reject_named_arguments("pane {pane:>4} closed", &["id", "pane"]);
+        });
+
+        assert!(named.is_err());
+    }
 }
@@ -0,0 +1,42 @@
+//! Information about the current process, computed once and cached for the
+//! [ProcessId](crate::format::FormatItem::ProcessId),
+//! [Hostname](crate::format::FormatItem::Hostname) and
+//! [ProgramName](crate::format::FormatItem::ProgramName) items.
+
+use lazy_static::lazy_static;
+use std::env;
+use std::path::Path;
+
+lazy_static! {
+    static ref PROCESS_ID: u32 = std::process::id();
+    static ref HOSTNAME: String = gethostname::gethostname().to_string_lossy().into_owned();
+    static ref PROGRAM_NAME: String = find_program_name().unwrap_or_default();
+}
+
+/// Get the id of the current process.
+pub(crate) fn process_id() -> u32 {
+    return *PROCESS_ID;
+}
+
+/// Get the hostname of the machine.
+pub(crate) fn hostname() -> &'static str {
+    return &HOSTNAME;
+}
+
+/// Get the name of the running executable without its extension, or an empty string if it can't
+/// be found.
+pub(crate) fn program_name() -> &'static str {
+    return &PROGRAM_NAME;
+}
+
+/// Finds the name of the executable from its path, falling back to the first argument.
+fn find_program_name() -> Option<String> {
+    let path = match env::current_exe() {
+        Ok(path) => path,
+        Err(_) => env::args_os().next()?.into(),
+    };
+
+    return Path::new(&path)
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned());
+}
@@ -0,0 +1,342 @@
+//! Rotation policies used by the [FileLogger](crate::logger::FileLogger) to limit the size of log
+//! files.
+
+use chrono::format::{parse, Item, Parsed, StrftimeItems};
+use chrono::{DateTime, Datelike, TimeZone, Timelike};
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// Rotates a log file once writing another log would take it past a maximum size.
+///
+/// When rotating, `muxide.log` is renamed to `muxide.log.1`, `muxide.log.1` to `muxide.log.2` and
+/// so on up to the number of backups, the oldest backup is deleted and a new, empty `muxide.log`
+/// is opened.
+///
+/// # Example
+/// ```no_run
+/// use chrono::Local;
+/// use muxide_logging::logger::FileLogger;
+/// use muxide_logging::rotation::SizeRotation;
+///
+/// let mut logger = FileLogger::<Local>::new();
+/// // Keep at most 10MB in muxide.log and the 3 previous files in muxide.log.1 to muxide.log.3
+/// logger.open_file_with_rotation("muxide.log", SizeRotation::new(10_000_000, 3)).unwrap();
+/// ```
+pub struct SizeRotation {
+    max_bytes: u64,
+    backups: usize,
+}
+
+impl SizeRotation {
+    /// Create a new [SizeRotation] that rotates files before they exceed `max_bytes` and keeps
+    /// `backups` previous files.
+    pub const fn new(max_bytes: u64, backups: usize) -> Self {
+        return Self { max_bytes, backups };
+    }
+
+    /// The maximum size of a log file in bytes. A single log larger than this is still written,
+    /// to a file of its own.
+    pub const fn max_bytes(&self) -> u64 {
+        return self.max_bytes;
+    }
+
+    /// The number of previous log files to keep.
+    pub const fn backups(&self) -> usize {
+        return self.backups;
+    }
+
+    /// Returns true if a log of `len` bytes should be written to a new file given a current file
+    /// of `current_size` bytes.
+    pub(crate) fn should_rotate(&self, current_size: u64, len: u64) -> bool {
+        return current_size > 0 && current_size.saturating_add(len) > self.max_bytes;
+    }
+
+    /// Shift each backup of `path` up by one, removing the oldest, and move `path` itself to the
+    /// first backup. If no backups are kept `path` is removed instead.
+    pub(crate) fn rotate(&self, path: &Path) -> io::Result<()> {
+        if self.backups == 0 {
+            return remove_if_exists(path);
+        }
+
+        remove_if_exists(&backup_path(path, self.backups))?;
+
+        for index in (1..self.backups).rev() {
+            let from = backup_path(path, index);
+
+            if from.exists() {
+                fs::rename(from, backup_path(path, index + 1))?;
+            }
+        }
+
+        return fs::rename(path, backup_path(path, 1));
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// How often a [TimeRotation] starts a new file.
+pub enum RotationPeriod {
+    /// Start a new file at the beginning of every hour.
+    Hourly,
+    /// Start a new file at midnight.
+    Daily,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// Starts a new log file every hour or day. The path of each file is built by expanding any
+/// [strftime](chrono::format::strftime) patterns in the configured path using the time the file is
+/// started, in the timezone of the logger. Patterns should therefore only use fields that stay the
+/// same throughout a period, such as `%Y-%m-%d` for daily rotation.
+///
+/// Files matching the pattern whose period ended longer ago than the retention window are deleted
+/// whenever a new file is started. The period of a file is read from the time in its name, so
+/// files are aged by the logger's clock rather than by when they were last modified, and a file is
+/// only deleted if the pattern includes its date. Only the file name may contain patterns when
+/// using a retention window, as only the directory of the current file is searched.
+///
+/// # Example
+/// ```no_run
+/// use chrono::Local;
+/// use muxide_logging::logger::FileLogger;
+/// use muxide_logging::rotation::TimeRotation;
+/// use std::time::Duration;
+///
+/// let mut logger = FileLogger::<Local>::new();
+/// // Writes to e.g. muxide-2026-10-16.log, deleting files older than a week.
+/// logger
+///     .open_file_with_time_rotation(
+///         "muxide-%Y-%m-%d.log",
+///         TimeRotation::daily().with_retention(Duration::from_secs(7 * 24 * 60 * 60)),
+///     )
+///     .unwrap();
+/// ```
+pub struct TimeRotation {
+    period: RotationPeriod,
+    retention: Option<Duration>,
+}
+
+impl TimeRotation {
+    /// Create a new [TimeRotation] with the specified period that keeps every file.
+    pub const fn new(period: RotationPeriod) -> Self {
+        return Self {
+            period,
+            retention: None,
+        };
+    }
+
+    /// Create a new [TimeRotation] that starts a new file each hour.
+    pub const fn hourly() -> Self {
+        return Self::new(RotationPeriod::Hourly);
+    }
+
+    /// Create a new [TimeRotation] that starts a new file each day.
+    pub const fn daily() -> Self {
+        return Self::new(RotationPeriod::Daily);
+    }
+
+    /// Delete previous files whose period ended longer ago than `retention`.
+    pub const fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = Some(retention);
+
+        return self;
+    }
+
+    /// How often a new file is started.
+    pub const fn period(&self) -> RotationPeriod {
+        return self.period;
+    }
+
+    /// How long previous files are kept for, if they are ever deleted.
+    pub const fn retention(&self) -> Option<Duration> {
+        return self.retention;
+    }
+
+    /// Identifies the period that `time` falls within, when it changes a new file is started.
+    pub(crate) fn period_key<Tz: TimeZone>(&self, time: &DateTime<Tz>) -> (i32, u32, u32) {
+        return match self.period {
+            RotationPeriod::Hourly => (time.year(), time.ordinal(), time.hour()),
+            RotationPeriod::Daily => (time.year(), time.ordinal(), 0),
+        };
+    }
+
+    /// Delete any files in the same directory as `current` that match the file name of
+    /// `pattern` and whose period ended longer than the retention window before `now`.
+    pub(crate) fn remove_expired<Tz: TimeZone>(
+        &self,
+        pattern: &str,
+        current: &Path,
+        now: &DateTime<Tz>,
+    ) -> io::Result<()> {
+        let retention = match self.retention {
+            Some(retention) => retention,
+            None => return Ok(()),
+        };
+
+        let file_pattern = match Path::new(pattern)
+            .file_name()
+            .and_then(|name| name.to_str())
+        {
+            Some(file_pattern) => file_pattern,
+            None => return Ok(()),
+        };
+
+        let cutoff = match chrono::Duration::from_std(retention)
+            .ok()
+            .and_then(|retention| now.clone().checked_sub_signed(retention))
+        {
+            Some(cutoff) => cutoff,
+            None => return Ok(()),
+        };
+
+        let directory = match current.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+
+        for entry in fs::read_dir(directory)? {
+            let entry = entry?;
+
+            if entry.file_name() == current.file_name().unwrap_or_default() {
+                continue;
+            }
+
+            let end = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| self.period_end(name, file_pattern, &now.timezone()));
+
+            if end.is_some_and(|end| end < cutoff) {
+                remove_if_exists(&entry.path())?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// The end of the period of the file `name`, read from the time its name was expanded from
+    /// `pattern` with. Returns [None] if the name doesn't match the pattern or lacks a date.
+    pub(crate) fn period_end<Tz: TimeZone>(
+        &self,
+        name: &str,
+        pattern: &str,
+        timezone: &Tz,
+    ) -> Option<DateTime<Tz>> {
+        let mut parsed = Parsed::new();
+        parse(&mut parsed, name, StrftimeItems::new(pattern)).ok()?;
+
+        let date = parsed.to_naive_date().ok()?;
+        let (hour, length) = match self.period {
+            RotationPeriod::Hourly => (
+                parsed.hour_div_12.unwrap_or(0) * 12 + parsed.hour_mod_12.unwrap_or(0),
+                chrono::Duration::hours(1),
+            ),
+            RotationPeriod::Daily => (0, chrono::Duration::days(1)),
+        };
+        let start = date.and_hms_opt(hour, 0, 0)?;
+
+        return timezone.from_local_datetime(&(start + length)).earliest();
+    }
+}
+
+/// Expand the strftime patterns in `pattern` using `time`.
+pub(crate) fn expand_path<Tz: TimeZone>(pattern: &str, time: &DateTime<Tz>) -> PathBuf
+where
+    Tz::Offset: std::fmt::Display,
+{
+    return PathBuf::from(time.format(pattern).to_string());
+}
+
+/// Check that every strftime pattern in `pattern` is valid, formatting an invalid pattern panics.
+pub(crate) fn validate_pattern(pattern: &str) -> io::Result<()> {
+    if StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid strftime pattern in path '{}'", pattern),
+        ));
+    }
+
+    return Ok(());
+}
+
+/// The path of the backup with the specified index, e.g. `muxide.log.2`.
+pub fn backup_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(".{}", index));
+
+    return PathBuf::from(name);
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    return match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rotation::{expand_path, validate_pattern, TimeRotation};
+    use chrono::{TimeZone, Utc};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_period_key() {
+        let before = Utc.ymd(2026, 10, 16).and_hms(9, 59, 59);
+        let after = Utc.ymd(2026, 10, 16).and_hms(10, 0, 0);
+
+        assert_ne!(
+            TimeRotation::hourly().period_key(&before),
+            TimeRotation::hourly().period_key(&after)
+        );
+        assert_eq!(
+            TimeRotation::daily().period_key(&before),
+            TimeRotation::daily().period_key(&after)
+        );
+        assert_ne!(
+            TimeRotation::daily().period_key(&after),
+            TimeRotation::daily().period_key(&Utc.ymd(2026, 10, 17).and_hms(0, 0, 0))
+        );
+    }
+
+    #[test]
+    fn test_period_end() {
+        let daily = TimeRotation::daily();
+        let hourly = TimeRotation::hourly();
+
+        assert_eq!(
+            daily.period_end("muxide-2026-10-16.log", "muxide-%Y-%m-%d.log", &Utc),
+            Some(Utc.ymd(2026, 10, 17).and_hms(0, 0, 0))
+        );
+        assert_eq!(
+            hourly.period_end("muxide-2026-10-16-09.log", "muxide-%Y-%m-%d-%H.log", &Utc),
+            Some(Utc.ymd(2026, 10, 16).and_hms(10, 0, 0))
+        );
+        assert_eq!(
+            daily.period_end("muxide-2026-10-16.log.1", "muxide-%Y-%m-%d.log", &Utc),
+            None
+        );
+        assert_eq!(
+            daily.period_end("other.log", "muxide-%Y-%m-%d.log", &Utc),
+            None
+        );
+        assert_eq!(
+            daily.period_end("muxide-09.log", "muxide-%H.log", &Utc),
+            None
+        );
+    }
+
+    #[test]
+    fn test_expand_path() {
+        let time = Utc.ymd(2026, 10, 16).and_hms(9, 30, 0);
+
+        assert_eq!(
+            expand_path("logs/muxide-%Y-%m-%d-%H.log", &time),
+            PathBuf::from("logs/muxide-2026-10-16-09.log")
+        );
+        assert!(validate_pattern("muxide-%Y.log").is_ok());
+        assert!(validate_pattern("muxide-%Q.log").is_err());
+    }
+}
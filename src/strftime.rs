@@ -0,0 +1,307 @@
+//! Writing times with strftime patterns straight into a writer, without the temporary strings
+//! built by chrono's [format](chrono::DateTime::format).
+
+use chrono::format::{Fixed, Item, Numeric, Pad, StrftimeItems};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveTime, Offset, TimeZone, Timelike};
+use std::fmt::{self, Display, Write};
+use std::iter;
+
+const SHORT_MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+const LONG_MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const SHORT_WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const LONG_WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Writes `time` using the strftime `pattern`, producing the same output as
+/// [format](chrono::DateTime::format). Nothing is written if the pattern is invalid.
+pub(crate) fn write_time<W: Write, Tz: TimeZone>(
+    writer: &mut W,
+    time: &DateTime<Tz>,
+    pattern: &str,
+) -> fmt::Result
+where
+    Tz::Offset: Display,
+{
+    if StrftimeItems::new(pattern).any(|item| item == Item::Error) {
+        return Err(fmt::Error);
+    }
+
+    let date = time.naive_local().date();
+    let naive_time = time.naive_local().time();
+
+    for item in StrftimeItems::new(pattern) {
+        match item {
+            Item::Literal(s) | Item::Space(s) => writer.write_str(s)?,
+            Item::OwnedLiteral(ref s) | Item::OwnedSpace(ref s) => writer.write_str(s)?,
+            Item::Numeric(ref numeric, ref pad) => {
+                write_numeric(writer, time, &date, &naive_time, numeric, pad)?
+            }
+            Item::Fixed(ref fixed) => write_fixed(writer, time, &date, &naive_time, fixed)?,
+            Item::Error => return Err(fmt::Error),
+        }
+    }
+
+    return Ok(());
+}
+
+/// Writes a numeric item, padded to its usual width.
+fn write_numeric<W: Write, Tz: TimeZone>(
+    writer: &mut W,
+    time: &DateTime<Tz>,
+    date: &NaiveDate,
+    naive_time: &NaiveTime,
+    numeric: &Numeric,
+    pad: &Pad,
+) -> fmt::Result
+where
+    Tz::Offset: Display,
+{
+    let week_from = |days_from_start: u32| {
+        return (date.ordinal() as i64 - days_from_start as i64 + 7) / 7;
+    };
+
+    let (width, value) = match numeric {
+        Numeric::Year => (4, date.year() as i64),
+        Numeric::YearDiv100 => (2, (date.year() as i64).div_euclid(100)),
+        Numeric::YearMod100 => (2, (date.year() as i64).rem_euclid(100)),
+        Numeric::IsoYear => (4, date.iso_week().year() as i64),
+        Numeric::IsoYearDiv100 => (2, (date.iso_week().year() as i64).div_euclid(100)),
+        Numeric::IsoYearMod100 => (2, (date.iso_week().year() as i64).rem_euclid(100)),
+        Numeric::Month => (2, date.month() as i64),
+        Numeric::Day => (2, date.day() as i64),
+        Numeric::WeekFromSun => (2, week_from(date.weekday().num_days_from_sunday())),
+        Numeric::WeekFromMon => (2, week_from(date.weekday().num_days_from_monday())),
+        Numeric::IsoWeek => (2, date.iso_week().week() as i64),
+        Numeric::NumDaysFromSun => (1, date.weekday().num_days_from_sunday() as i64),
+        Numeric::WeekdayFromMon => (1, date.weekday().number_from_monday() as i64),
+        Numeric::Ordinal => (3, date.ordinal() as i64),
+        Numeric::Hour => (2, naive_time.hour() as i64),
+        Numeric::Hour12 => (2, naive_time.hour12().1 as i64),
+        Numeric::Minute => (2, naive_time.minute() as i64),
+        Numeric::Second => (
+            2,
+            (naive_time.second() + naive_time.nanosecond() / 1_000_000_000) as i64,
+        ),
+        Numeric::Nanosecond => (9, (naive_time.nanosecond() % 1_000_000_000) as i64),
+        Numeric::Timestamp => (1, time.timestamp()),
+        // Any other item is left to chrono.
+        _ => {
+            return write!(
+                writer,
+                "{}",
+                time.format_with_items(iter::once(Item::Numeric(numeric.clone(), *pad)))
+            )
+        }
+    };
+
+    let is_year = *numeric == Numeric::Year || *numeric == Numeric::IsoYear;
+
+    // Years without four digits are written with an explicit sign, as required by ISO 8601.
+    return if is_year && !(0..10_000).contains(&value) {
+        match pad {
+            Pad::None => write!(writer, "{:+}", value),
+            Pad::Zero => write!(writer, "{:+01$}", value, width + 1),
+            Pad::Space => write!(writer, "{:+1$}", value, width + 1),
+        }
+    } else {
+        match pad {
+            Pad::None => write!(writer, "{}", value),
+            Pad::Zero => write!(writer, "{:01$}", value, width),
+            Pad::Space => write!(writer, "{:1$}", value, width),
+        }
+    };
+}
+
+/// Writes a fixed item, such as a name or an offset.
+#[allow(clippy::manual_is_multiple_of)]
+fn write_fixed<W: Write, Tz: TimeZone>(
+    writer: &mut W,
+    time: &DateTime<Tz>,
+    date: &NaiveDate,
+    naive_time: &NaiveTime,
+    fixed: &Fixed,
+) -> fmt::Result
+where
+    Tz::Offset: Display,
+{
+    let nanosecond = naive_time.nanosecond() % 1_000_000_000;
+    let offset = time.offset().fix().local_minus_utc();
+
+    return match fixed {
+        Fixed::ShortMonthName => writer.write_str(SHORT_MONTHS[date.month0() as usize]),
+        Fixed::LongMonthName => writer.write_str(LONG_MONTHS[date.month0() as usize]),
+        Fixed::ShortWeekdayName => {
+            writer.write_str(SHORT_WEEKDAYS[date.weekday().num_days_from_sunday() as usize])
+        }
+        Fixed::LongWeekdayName => {
+            writer.write_str(LONG_WEEKDAYS[date.weekday().num_days_from_sunday() as usize])
+        }
+        Fixed::LowerAmPm => writer.write_str(if naive_time.hour12().0 { "pm" } else { "am" }),
+        Fixed::UpperAmPm => writer.write_str(if naive_time.hour12().0 { "PM" } else { "AM" }),
+        Fixed::Nanosecond => {
+            if nanosecond == 0 {
+                Ok(())
+            } else if nanosecond % 1_000_000 == 0 {
+                write!(writer, ".{:03}", nanosecond / 1_000_000)
+            } else if nanosecond % 1_000 == 0 {
+                write!(writer, ".{:06}", nanosecond / 1_000)
+            } else {
+                write!(writer, ".{:09}", nanosecond)
+            }
+        }
+        Fixed::Nanosecond3 => write!(writer, ".{:03}", nanosecond / 1_000_000),
+        Fixed::Nanosecond6 => write!(writer, ".{:06}", nanosecond / 1_000),
+        Fixed::Nanosecond9 => write!(writer, ".{:09}", nanosecond),
+        Fixed::TimezoneName => write!(writer, "{}", time.offset()),
+        Fixed::TimezoneOffsetColon => write_offset(writer, offset, false, true),
+        Fixed::TimezoneOffsetColonZ => write_offset(writer, offset, true, true),
+        Fixed::TimezoneOffset => write_offset(writer, offset, false, false),
+        Fixed::TimezoneOffsetZ => write_offset(writer, offset, true, false),
+        Fixed::RFC2822 => {
+            write!(
+                writer,
+                "{}, {:02} {} {:04} {:02}:{:02}:{:02} ",
+                SHORT_WEEKDAYS[date.weekday().num_days_from_sunday() as usize],
+                date.day(),
+                SHORT_MONTHS[date.month0() as usize],
+                date.year(),
+                naive_time.hour(),
+                naive_time.minute(),
+                naive_time.second() + naive_time.nanosecond() / 1_000_000_000
+            )?;
+
+            write_offset(writer, offset, false, false)
+        }
+        Fixed::RFC3339 => {
+            write!(writer, "{:?}T{:?}", date, naive_time)?;
+
+            write_offset(writer, offset, false, true)
+        }
+        // The internal items, such as `%3f`, are left to chrono.
+        _ => write!(
+            writer,
+            "{}",
+            time.format_with_items(iter::once(Item::Fixed(fixed.clone())))
+        ),
+    };
+}
+
+/// Writes an offset from UTC in seconds as `+HHMM` or `+HH:MM`, or as `Z` if it is zero and
+/// `allow_zulu` is true.
+fn write_offset<W: Write>(
+    writer: &mut W,
+    offset: i32,
+    allow_zulu: bool,
+    use_colon: bool,
+) -> fmt::Result {
+    if allow_zulu && offset == 0 {
+        return writer.write_char('Z');
+    }
+
+    let (sign, offset) = if offset < 0 {
+        ('-', -offset)
+    } else {
+        ('+', offset)
+    };
+
+    if use_colon {
+        return write!(
+            writer,
+            "{}{:02}:{:02}",
+            sign,
+            offset / 3600,
+            offset / 60 % 60
+        );
+    } else {
+        return write!(
+            writer,
+            "{}{:02}{:02}",
+            sign,
+            offset / 3600,
+            offset / 60 % 60
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_time;
+    use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+    fn written<Tz: chrono::TimeZone>(time: &DateTime<Tz>, pattern: &str) -> Option<String>
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let mut output = String::new();
+
+        return write_time(&mut output, time, pattern).ok().map(|_| output);
+    }
+
+    #[test]
+    fn test_matches_chrono() {
+        let patterns = [
+            "%Y-%m-%d %H:%M:%S",
+            "%k:%M:%S",
+            "%C %y %G %g %V %U %W %w %u %j",
+            "%I:%M %p %P",
+            "%a %A %b %B %h %e",
+            "%f %.f %.3f %.6f %.9f %3f %6f %9f",
+            "%z %:z %Z",
+            "%s %D %F %T %R %r %c %x %X",
+            "%-d %_m %0e %n%t%%",
+            "%+",
+        ];
+        let offset = FixedOffset::west(5 * 3600 + 30 * 60);
+        let times = [
+            offset.ymd(2003, 7, 1).and_hms_nano(20, 52, 37, 123_456_789),
+            offset.ymd(-44, 3, 15).and_hms_milli(0, 0, 0, 250),
+            offset.ymd(12_345, 12, 31).and_hms(12, 0, 0),
+        ];
+
+        for time in times.iter() {
+            for pattern in patterns.iter() {
+                assert_eq!(
+                    written(time, pattern),
+                    Some(time.format(pattern).to_string()),
+                    "{}",
+                    pattern
+                );
+            }
+        }
+
+        let utc = Utc.ymd(2021, 1, 2).and_hms(3, 4, 5);
+        assert_eq!(
+            written(&utc, "%Z %:z %+"),
+            Some(utc.format("%Z %:z %+").to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let time = Utc.ymd(2021, 1, 2).and_hms(3, 4, 5);
+
+        assert_eq!(written(&time, "%Y %Q"), None);
+    }
+}
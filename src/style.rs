@@ -0,0 +1,296 @@
+//! ANSI colours and styles used by the styling [FormatItem](crate::format::FormatItem)s.
+
+use crate::log::LogLevel;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// The environment variable that disables colour when set to a non-empty value, see
+/// [no-color.org](https://no-color.org).
+pub const NO_COLOR_ENV_VAR: &str = "NO_COLOR";
+
+/// The escape sequence that resets all styles.
+pub(crate) const RESET: &str = "\x1b[0m";
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// A foreground colour.
+pub enum Colour {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// A style applied to all following output until a [Reset](crate::format::FormatItem::Reset).
+pub enum Style {
+    Bold,
+    Dim,
+    Italic,
+    Underline,
+    Colour(Colour),
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, Default)]
+/// Whether the styling items of a [Format](crate::format::Format) produce escape codes.
+pub enum ColourMode {
+    /// Use colour unless the `NO_COLOR` environment variable is set. Loggers may resolve this
+    /// further, for example [FileLogger](crate::logger::FileLogger) never uses colour and
+    /// [ConsoleLogger](crate::logger::ConsoleLogger) only uses colour when writing to a terminal.
+    #[default]
+    Auto,
+    /// Always produce escape codes.
+    Always,
+    /// Never produce escape codes, styling items are skipped.
+    Never,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash)]
+/// The colour used for each [LogLevel] by [LevelColour](crate::format::FormatItem::LevelColour).
+///
+/// # Example
+/// ```
+/// use muxide_logging::log::LogLevel;
+/// use muxide_logging::style::{Colour, Palette};
+///
+/// let palette = Palette::default().set(LogLevel::Information, Colour::White);
+///
+/// assert_eq!(palette.colour(LogLevel::Information), Colour::White);
+/// assert_eq!(palette.colour(LogLevel::Error), Colour::Red);
+/// ```
+pub struct Palette {
+    /// Indexed by the level's severity in tens, Trace to Critical.
+    colours: [Colour; 7],
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+/// The error returned when parsing an unknown [Style] name.
+pub struct ParseStyleError {
+    name: String,
+}
+
+impl Colour {
+    /// All colours, in the order of their escape codes.
+    pub const ALL: [Colour; 16] = [
+        Colour::Black,
+        Colour::Red,
+        Colour::Green,
+        Colour::Yellow,
+        Colour::Blue,
+        Colour::Magenta,
+        Colour::Cyan,
+        Colour::White,
+        Colour::BrightBlack,
+        Colour::BrightRed,
+        Colour::BrightGreen,
+        Colour::BrightYellow,
+        Colour::BrightBlue,
+        Colour::BrightMagenta,
+        Colour::BrightCyan,
+        Colour::BrightWhite,
+    ];
+
+    /// The name of the colour in the pattern syntax, for example `bright_red`.
+    pub const fn as_str(self) -> &'static str {
+        return match self {
+            Colour::Black => "black",
+            Colour::Red => "red",
+            Colour::Green => "green",
+            Colour::Yellow => "yellow",
+            Colour::Blue => "blue",
+            Colour::Magenta => "magenta",
+            Colour::Cyan => "cyan",
+            Colour::White => "white",
+            Colour::BrightBlack => "bright_black",
+            Colour::BrightRed => "bright_red",
+            Colour::BrightGreen => "bright_green",
+            Colour::BrightYellow => "bright_yellow",
+            Colour::BrightBlue => "bright_blue",
+            Colour::BrightMagenta => "bright_magenta",
+            Colour::BrightCyan => "bright_cyan",
+            Colour::BrightWhite => "bright_white",
+        };
+    }
+
+    /// The SGR parameter selecting this foreground colour.
+    const fn code(self) -> u8 {
+        return match self {
+            Colour::Black => 30,
+            Colour::Red => 31,
+            Colour::Green => 32,
+            Colour::Yellow => 33,
+            Colour::Blue => 34,
+            Colour::Magenta => 35,
+            Colour::Cyan => 36,
+            Colour::White => 37,
+            Colour::BrightBlack => 90,
+            Colour::BrightRed => 91,
+            Colour::BrightGreen => 92,
+            Colour::BrightYellow => 93,
+            Colour::BrightBlue => 94,
+            Colour::BrightMagenta => 95,
+            Colour::BrightCyan => 96,
+            Colour::BrightWhite => 97,
+        };
+    }
+}
+
+impl Style {
+    /// The escape sequence that applies this style.
+    pub fn escape_code(self) -> String {
+        return format!("\x1b[{}m", self.code());
+    }
+
+    /// Write the escape sequence that applies this style to `writer`.
+    pub(crate) fn write_escape_code<W: fmt::Write>(self, writer: &mut W) -> fmt::Result {
+        return write!(writer, "\x1b[{}m", self.code());
+    }
+
+    /// The SGR parameter of the style.
+    const fn code(self) -> u8 {
+        return match self {
+            Style::Bold => 1,
+            Style::Dim => 2,
+            Style::Italic => 3,
+            Style::Underline => 4,
+            Style::Colour(colour) => colour.code(),
+        };
+    }
+}
+
+impl ColourMode {
+    /// Returns true if escape codes should be produced. [Auto](ColourMode::Auto) checks the
+    /// `NO_COLOR` environment variable.
+    pub fn is_enabled(self) -> bool {
+        return match self {
+            ColourMode::Auto => !no_color(),
+            ColourMode::Always => true,
+            ColourMode::Never => false,
+        };
+    }
+}
+
+/// Returns true if the `NO_COLOR` environment variable is set to a non-empty value.
+pub(crate) fn no_color() -> bool {
+    return std::env::var_os(NO_COLOR_ENV_VAR).is_some_and(|value| !value.is_empty());
+}
+
+impl Palette {
+    /// Create the default palette.
+    pub const fn new() -> Self {
+        return Self {
+            colours: [
+                Colour::BrightBlack,
+                Colour::Blue,
+                Colour::Green,
+                Colour::Cyan,
+                Colour::Yellow,
+                Colour::Red,
+                Colour::Magenta,
+            ],
+        };
+    }
+
+    /// Use `colour` for `level`.
+    pub fn set(mut self, level: LogLevel, colour: Colour) -> Self {
+        self.colours[Self::index(level)] = colour;
+
+        return self;
+    }
+
+    /// Get the colour used for `level`.
+    pub fn colour(&self, level: LogLevel) -> Colour {
+        return self.colours[Self::index(level)];
+    }
+
+    fn index(level: LogLevel) -> usize {
+        return (level.severity() / 10) as usize;
+    }
+}
+
+impl Default for Palette {
+    /// Trace is bright black, Debug blue, Information green, StateChange cyan, Warning yellow,
+    /// Error red and Critical magenta.
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl Display for Style {
+    /// Writes the name of the style in the pattern syntax, see [FromStr].
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return match self {
+            Style::Bold => f.write_str("bold"),
+            Style::Dim => f.write_str("dim"),
+            Style::Italic => f.write_str("italic"),
+            Style::Underline => f.write_str("underline"),
+            Style::Colour(colour) => f.write_str(colour.as_str()),
+        };
+    }
+}
+
+impl FromStr for Style {
+    type Err = ParseStyleError;
+
+    /// Parses `bold`, `dim`, `italic`, `underline` or the name of a [Colour].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s {
+            "bold" => Ok(Style::Bold),
+            "dim" => Ok(Style::Dim),
+            "italic" => Ok(Style::Italic),
+            "underline" => Ok(Style::Underline),
+            _ => Colour::ALL
+                .iter()
+                .find(|colour| colour.as_str() == s)
+                .map(|colour| Style::Colour(*colour))
+                .ok_or_else(|| ParseStyleError {
+                    name: s.to_string(),
+                }),
+        };
+    }
+}
+
+impl ParseStyleError {
+    /// The name that could not be parsed.
+    pub fn name(&self) -> &str {
+        return &self.name;
+    }
+}
+
+impl Display for ParseStyleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        return write!(f, "unknown style '{}'", self.name);
+    }
+}
+
+impl std::error::Error for ParseStyleError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::style::{Colour, Style};
+
+    #[test]
+    fn test_style_names() {
+        for colour in Colour::ALL.iter() {
+            let style = Style::Colour(*colour);
+
+            assert_eq!(style.to_string().parse::<Style>().unwrap(), style);
+        }
+
+        assert_eq!("bold".parse::<Style>().unwrap(), Style::Bold);
+        assert_eq!("loud".parse::<Style>().unwrap_err().name(), "loud");
+        assert_eq!(Style::Colour(Colour::BrightRed).escape_code(), "\x1b[91m");
+        assert_eq!(Style::Underline.escape_code(), "\x1b[4m");
+    }
+}
@@ -0,0 +1,58 @@
+//! Support for the timezones used by [Format](crate::format::Format) and the loggers.
+//!
+//! Any [TimeZone] can be used, "now" is always obtained in the timezone of the format or logger.
+//! A specific timezone, such as a [FixedOffset] other than the local one, is supplied to
+//! constructors such as [Format::new_in](crate::format::Format::new_in) and
+//! [FileLogger::new_in](crate::logger::FileLogger::new_in). The constructors that don't take a
+//! timezone use the [DefaultTimeZone] of the type.
+//!
+//! Named timezones are available from the [chrono-tz](https://docs.rs/chrono-tz/) crate, which is
+//! re-exported as `muxide_logging::chrono_tz` when the `chrono-tz` feature is enabled.
+
+use chrono::{DateTime, FixedOffset, Local, Offset, TimeZone, Utc};
+
+/// A timezone that can be created without any configuration, used by constructors that don't take
+/// a timezone such as [Format::new_tz](crate::format::Format::new_tz). It is implemented for every
+/// timezone in chrono and can be implemented for other timezones.
+pub trait DefaultTimeZone: TimeZone {
+    /// Create an instance of the timezone.
+    fn default_timezone() -> Self;
+}
+
+impl DefaultTimeZone for Local {
+    fn default_timezone() -> Self {
+        return Local;
+    }
+}
+
+impl DefaultTimeZone for Utc {
+    fn default_timezone() -> Self {
+        return Utc;
+    }
+}
+
+/// The offset of the [Local] timezone at the time it is created.
+impl DefaultTimeZone for FixedOffset {
+    fn default_timezone() -> Self {
+        return Local::now().offset().fix();
+    }
+}
+
+/// The UTC timezone.
+#[cfg(feature = "chrono-tz")]
+impl DefaultTimeZone for chrono_tz::Tz {
+    fn default_timezone() -> Self {
+        return chrono_tz::UTC;
+    }
+}
+
+/// Get the current time in `timezone` from the clock of the logger the macros are using, which is
+/// set with [set_clock](crate::set_clock) for the default logger.
+pub(crate) fn now<Tz: TimeZone>(timezone: &Tz) -> DateTime<Tz> {
+    let now = match crate::DEFAULT_CLOCK.read() {
+        Ok(clock) => clock.now(),
+        Err(_) => Utc::now(),
+    };
+
+    return now.with_timezone(timezone);
+}
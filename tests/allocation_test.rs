@@ -0,0 +1,140 @@
+mod shared;
+
+use muxide_logging::format::{Encoding, Format, FormatItem};
+use muxide_logging::info;
+use muxide_logging::log::{LogItem, LogLevel, Logger};
+use muxide_logging::logger::FileLogger;
+use shared::*;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fs;
+use std::path::Path;
+
+/// Counts the allocations made by each thread, so other tests running at the same time don't
+/// affect the count.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+
+        return System.alloc(layout);
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+
+        return System.realloc(ptr, layout, new_size);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// The number of allocations made by the current thread while running `f`.
+fn allocations<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+
+    return ALLOCATIONS.with(Cell::get) - before;
+}
+
+fn item() -> LogItem<chrono::Utc> {
+    let format = Format::new_tz()
+        .set_module_path("muxide::server::session::pane")
+        .set_line(12)
+        .set_current_thread();
+
+    return LogItem::new(format, LogLevel::Error, TEST_ERROR_MESSAGE)
+        .with_field("pane", 3)
+        .with_field("name", "main");
+}
+
+#[test]
+fn write_to_test() {
+    let fmt = Format::<chrono::Utc>::parse(
+        "[{sequence} {thread_name}] ({module:abbreviated} {line}) {level}: {message} {fields}",
+    )
+    .unwrap()
+    .set_module_path("muxide::server::session::pane")
+    .set_line(12);
+    let mut buffer = String::with_capacity(256);
+
+    let count = allocations(|| {
+        fmt.write_to(&mut buffer, LogLevel::Error, TEST_ERROR_MESSAGE)
+            .unwrap();
+    });
+
+    assert_eq!(count, 0);
+    assert_eq!(buffer, "[ ] (m::s::s::pane 12) Error: Error message ");
+}
+
+#[test]
+fn file_logger_test() {
+    if Path::new(ALLOCATION_TEST_FILE_NAME).exists() {
+        fs::remove_file(ALLOCATION_TEST_FILE_NAME).unwrap();
+    }
+
+    let mut logger = FileLogger::<chrono::Utc>::new();
+    logger.open_file(ALLOCATION_TEST_FILE_NAME).unwrap();
+    logger.set_override(
+        Format::parse("[{sequence} {elapsed}] ({module:last=2} {thread_name}) {level}: {message}")
+            .unwrap()
+            .append(FormatItem::CustomCharacter(' '))
+            .append(FormatItem::Fields),
+    );
+
+    for encoding in [Encoding::Text, Encoding::Json].iter() {
+        logger.set_encoding(*encoding);
+
+        // The first log grows the buffer to the size of a line.
+        logger.log_item(item());
+
+        let item = item();
+        let count = allocations(|| logger.log_item(item));
+
+        assert_eq!(count, 0, "{:?}", encoding);
+    }
+
+    drop(logger);
+    let contents = fs::read_to_string(ALLOCATION_TEST_FILE_NAME).unwrap();
+    fs::remove_file(ALLOCATION_TEST_FILE_NAME).unwrap();
+
+    assert_eq!(contents.lines().count(), 4);
+}
+
+#[test]
+fn macro_test() {
+    if Path::new(MACRO_ALLOCATION_TEST_FILE_NAME).exists() {
+        fs::remove_file(MACRO_ALLOCATION_TEST_FILE_NAME).unwrap();
+    }
+
+    let mut logger = FileLogger::<chrono::Local>::new();
+    logger.open_file(MACRO_ALLOCATION_TEST_FILE_NAME).unwrap();
+
+    // The first log grows the buffer to the size of a line.
+    info!(logger: logger, "Information message");
+
+    let count = allocations(|| {
+        info!(logger: logger, "Information message");
+    });
+
+    assert_eq!(count, 0);
+
+    drop(logger);
+    let contents = fs::read_to_string(MACRO_ALLOCATION_TEST_FILE_NAME).unwrap();
+    fs::remove_file(MACRO_ALLOCATION_TEST_FILE_NAME).unwrap();
+
+    assert_eq!(contents.lines().count(), 2);
+    assert!(contents
+        .lines()
+        .all(|line| line.ends_with(") Information: Information message")));
+}
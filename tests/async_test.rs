@@ -0,0 +1,211 @@
+use chrono::{DateTime, Local, TimeZone, Utc};
+use muxide_logging::clock::{Clock, MockClock};
+use muxide_logging::info;
+use muxide_logging::log::{LogItem, LogLevel, Logger, Metadata};
+use muxide_logging::logger::{AsyncLogger, FileLogger, OverflowPolicy};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// Records each message, optionally waiting for a signal before recording the first one so that
+/// the writer thread can be held up.
+struct RecordingLogger {
+    messages: Arc<Mutex<Vec<String>>>,
+    gate: Option<(Sender<()>, Receiver<()>)>,
+}
+
+impl Logger for RecordingLogger {
+    type ReturnType = ();
+
+    fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        if let Some((started, release)) = self.gate.take() {
+            started.send(()).unwrap();
+            release.recv().unwrap();
+        }
+
+        self.messages.lock().unwrap().push(item.into_message());
+    }
+}
+
+/// Records the timestamp of each log, reading the time from its own clock.
+struct ClockLogger {
+    clock: MockClock,
+    timestamps: Arc<Mutex<Vec<DateTime<Utc>>>>,
+}
+
+impl Logger for ClockLogger {
+    type ReturnType = ();
+
+    fn now(&self) -> DateTime<Utc> {
+        return self.clock.now();
+    }
+
+    fn clock(&self) -> Arc<dyn Clock> {
+        return Arc::new(self.clock.clone());
+    }
+
+    fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        self.timestamps
+            .lock()
+            .unwrap()
+            .push(item.timestamp().into());
+    }
+}
+
+fn overflow_test(policy: OverflowPolicy) -> (Vec<String>, u64) {
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let (started_sender, started) = channel();
+    let (release, release_receiver) = channel();
+
+    let mut logger = AsyncLogger::new(
+        RecordingLogger {
+            messages: messages.clone(),
+            gate: Some((started_sender, release_receiver)),
+        },
+        2,
+        policy,
+    );
+
+    info!(logger: logger, "0");
+    // The writer thread is now holding the first log, so the rest fill the queue.
+    started.recv().unwrap();
+
+    for i in 1..6 {
+        info!(&i.to_string(), logger);
+    }
+
+    release.send(()).unwrap();
+    logger.flush();
+
+    let dropped = logger.dropped();
+    drop(logger);
+
+    return (messages.lock().unwrap().clone(), dropped);
+}
+
+#[test]
+fn async_block_test() {
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let mut logger = AsyncLogger::new(
+        RecordingLogger {
+            messages: messages.clone(),
+            gate: None,
+        },
+        2,
+        OverflowPolicy::Block,
+    );
+
+    for i in 0..100 {
+        info!(&i.to_string(), logger);
+    }
+
+    logger.flush();
+
+    let expected: Vec<String> = (0..100).map(|i| i.to_string()).collect();
+    assert_eq!(*messages.lock().unwrap(), expected);
+    assert_eq!(logger.dropped(), 0);
+}
+
+#[test]
+fn async_drop_newest_test() {
+    assert_eq!(
+        overflow_test(OverflowPolicy::DropNewest),
+        (vec!["0".to_string(), "1".to_string(), "2".to_string()], 3)
+    );
+}
+
+#[test]
+fn async_drop_oldest_test() {
+    assert_eq!(
+        overflow_test(OverflowPolicy::DropOldest),
+        (vec!["0".to_string(), "4".to_string(), "5".to_string()], 3)
+    );
+}
+
+#[test]
+fn async_shutdown_test() {
+    let messages = Arc::new(Mutex::new(Vec::new()));
+    let mut logger = AsyncLogger::new(
+        RecordingLogger {
+            messages: messages.clone(),
+            gate: None,
+        },
+        16,
+        OverflowPolicy::Block,
+    );
+
+    for i in 0..10 {
+        info!(&i.to_string(), logger);
+    }
+
+    // Dropping writes every queued log before returning.
+    let inner = logger.into_inner().unwrap();
+
+    assert_eq!(inner.messages.lock().unwrap().len(), 10);
+}
+
+#[test]
+fn async_enabled_test() {
+    let mut file_logger = FileLogger::<Utc>::new();
+    file_logger.set_min_level(LogLevel::Warning);
+    let filter = file_logger.level_filter();
+
+    let logger = AsyncLogger::new(file_logger, 16, OverflowPolicy::Block);
+
+    assert!(!logger.enabled(&Metadata::new(LogLevel::Information, "muxide")));
+    assert!(logger.enabled(&Metadata::new(LogLevel::Error, "muxide")));
+
+    // The wrapped logger's filter is shared, so later changes are seen.
+    filter.set_enabled(vec![LogLevel::Information]);
+    assert!(logger.enabled(&Metadata::new(LogLevel::Information, "muxide")));
+    assert!(!logger.enabled(&Metadata::new(LogLevel::Error, "muxide")));
+
+    // Loggers without a filter accept every level.
+    let logger = AsyncLogger::new(
+        RecordingLogger {
+            messages: Arc::new(Mutex::new(Vec::new())),
+            gate: None,
+        },
+        16,
+        OverflowPolicy::Block,
+    );
+    assert!(logger.enabled(&Metadata::new(LogLevel::Trace, "muxide")));
+}
+
+#[test]
+fn async_clock_test() {
+    let time = Utc.ymd(2021, 6, 1).and_hms(12, 0, 0);
+    let clock = MockClock::new(time);
+    let timestamps = Arc::new(Mutex::new(Vec::new()));
+    let mut logger = AsyncLogger::new(
+        ClockLogger {
+            clock: clock.clone(),
+            timestamps: timestamps.clone(),
+        },
+        16,
+        OverflowPolicy::Block,
+    );
+
+    // Logs are timestamped with the wrapped logger's clock when they are queued.
+    assert_eq!(logger.now(), time);
+    info!(logger: logger, "First");
+    clock.advance(chrono::Duration::hours(1));
+    info!(logger: logger, "Second");
+    logger.flush();
+
+    assert_eq!(
+        *timestamps.lock().unwrap(),
+        vec![time, time + chrono::Duration::hours(1)]
+    );
+}
@@ -0,0 +1,143 @@
+mod shared;
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use muxide_logging::clock::{FixedClock, MockClock};
+use muxide_logging::format::{Format, FormatItem};
+use muxide_logging::log::{DynLogger, LogItem, LogLevel};
+use muxide_logging::logger::{FileLogger, StringLogger};
+use muxide_logging::rotation::TimeRotation;
+use muxide_logging::*;
+use shared::*;
+use std::path::Path;
+
+fn time() -> DateTime<Utc> {
+    return Utc.ymd(2021, 1, 1).and_hms(23, 59, 58);
+}
+
+#[test]
+fn fixed_clock_test() {
+    let mut logger = StringLogger::<Utc>::new_tz();
+    logger.set_clock(FixedClock::new(time()));
+    logger.set_override(Format::parse("{time:%F %T} {message}").unwrap());
+
+    assert_eq!(
+        info!(logger: logger, "Log message").unwrap(),
+        "2021-01-01 23:59:58 Log message"
+    );
+
+    // Trait objects use the clock of the boxed logger.
+    let logger: Box<dyn DynLogger> = Box::new(logger);
+    assert_eq!(logger.now_dyn(), time());
+}
+
+#[test]
+fn mock_clock_rotation_test() {
+    let pattern = "clock_rotation_test-%Y-%m-%d.log";
+    let first = "clock_rotation_test-2021-01-01.log";
+    let second = "clock_rotation_test-2021-01-02.log";
+
+    for name in [first, second].iter() {
+        if Path::new(name).exists() {
+            std::fs::remove_file(name).unwrap();
+        }
+    }
+
+    let clock = MockClock::new(time());
+    let mut logger = FileLogger::<Utc>::new();
+    logger.set_clock(clock.clone());
+    logger.set_override(
+        Format::new_tz()
+            .append(FormatItem::TimeString("%T ".to_string()))
+            .append(FormatItem::LogString),
+    );
+    logger
+        .open_file_with_time_rotation(pattern, TimeRotation::daily())
+        .unwrap();
+    assert_eq!(logger.path().unwrap(), Path::new(first));
+
+    info!(logger: logger, "first");
+    clock.advance(Duration::seconds(1));
+    info!(logger: logger, "second");
+    clock.advance(Duration::seconds(1));
+    info!(logger: logger, "third");
+    logger.close_file();
+
+    assert_eq!(
+        std::fs::read_to_string(first).unwrap(),
+        "23:59:58 first\n23:59:59 second\n"
+    );
+    assert_eq!(std::fs::read_to_string(second).unwrap(), "00:00:00 third\n");
+
+    std::fs::remove_file(first).unwrap();
+    std::fs::remove_file(second).unwrap();
+}
+
+#[test]
+fn mock_clock_retention_test() {
+    let pattern = "clock_retention_test-%Y-%m-%d.log";
+    let expired = "clock_retention_test-2021-01-01.log";
+    std::fs::write(expired, "").unwrap();
+
+    // Files are aged by the date in their name, so the expired file is kept until the clock
+    // passes the retention even though it was just modified.
+    let clock = MockClock::new(Utc.ymd(2021, 1, 5).and_hms(12, 0, 0));
+    let mut logger = FileLogger::<Utc>::new();
+    logger.set_clock(clock.clone());
+    logger
+        .open_file_with_time_rotation(
+            pattern,
+            TimeRotation::daily().with_retention(std::time::Duration::from_secs(7 * 24 * 60 * 60)),
+        )
+        .unwrap();
+    let first = logger.path().unwrap().to_path_buf();
+    assert!(Path::new(expired).exists());
+
+    clock.advance(Duration::days(8));
+    info!(logger: logger, "Log message");
+    let second = logger.path().unwrap().to_path_buf();
+    logger.close_file();
+
+    assert!(!Path::new(expired).exists());
+    assert!(!first.exists());
+    assert!(second.exists());
+
+    std::fs::remove_file(second).unwrap();
+}
+
+#[test]
+fn default_logger_clock_test() {
+    if Path::new(CLOCK_TEST_FILE_NAME).exists() {
+        std::fs::remove_file(CLOCK_TEST_FILE_NAME).unwrap();
+    }
+
+    set_clock(FixedClock::new(time())).unwrap();
+    DEFAULT_LOGGER
+        .lock()
+        .unwrap()
+        .set_override(Format::parse("{time:%F %T} {message}").unwrap());
+    set_output_file(CLOCK_TEST_FILE_NAME).unwrap();
+
+    error!(TEST_ERROR_MESSAGE);
+    close_output_file().unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(CLOCK_TEST_FILE_NAME).unwrap(),
+        format!(
+            "{} {}\n",
+            time().with_timezone(&Local).format("%F %T"),
+            TEST_ERROR_MESSAGE
+        )
+    );
+
+    // Items and formats created without a logger also use the default logger's clock.
+    let item = LogItem::<Utc>::new(Format::new_tz(), LogLevel::Error, TEST_ERROR_MESSAGE);
+    assert_eq!(item.timestamp(), time());
+    assert_eq!(
+        Format::<Utc>::parse("{time:%F %T}")
+            .unwrap()
+            .build_string(LogLevel::Error, ""),
+        "2021-01-01 23:59:58"
+    );
+
+    std::fs::remove_file(CLOCK_TEST_FILE_NAME).unwrap();
+}
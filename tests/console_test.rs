@@ -0,0 +1,49 @@
+use chrono::Local;
+use muxide_logging::error;
+use muxide_logging::format::Format;
+use muxide_logging::info;
+use muxide_logging::log::{LogItem, LogLevel, Logger};
+use muxide_logging::logger::{ConsoleLogger, Stream};
+
+#[test]
+fn stream_routing_test() {
+    let mut logger = ConsoleLogger::<Local>::new();
+
+    assert_eq!(logger.stream(LogLevel::Critical), Stream::Stderr);
+    assert_eq!(logger.stream(LogLevel::Error), Stream::Stderr);
+    assert_eq!(logger.stream(LogLevel::Warning), Stream::Stderr);
+    assert_eq!(logger.stream(LogLevel::StateChange), Stream::Stdout);
+    assert_eq!(logger.stream(LogLevel::Information), Stream::Stdout);
+    assert_eq!(logger.stream(LogLevel::Debug), Stream::Stdout);
+    assert_eq!(logger.stream(LogLevel::Trace), Stream::Stdout);
+
+    logger.set_stream(&[LogLevel::Warning], Stream::Stdout);
+    logger.set_stream(&[LogLevel::Debug, LogLevel::Debug], Stream::Stderr);
+    assert_eq!(logger.stream(LogLevel::Warning), Stream::Stdout);
+    assert_eq!(logger.stream(LogLevel::Debug), Stream::Stderr);
+    assert_eq!(
+        logger.is_terminal(Stream::Stdout),
+        Stream::Stdout.is_terminal()
+    );
+
+    info!(logger: logger, "Console logger test message");
+    error!(logger: logger, "Console logger test message");
+}
+
+#[test]
+fn console_levels_test() {
+    let mut logger = ConsoleLogger::<Local>::new();
+    let item = |level| {
+        return LogItem::new(Format::default(), level, "");
+    };
+
+    logger.set_min_level(LogLevel::Information);
+    logger.restrict_log_levels(&[LogLevel::Warning]);
+    assert!(!logger.can_log_item(&item(LogLevel::Debug)));
+    assert!(!logger.can_log_item(&item(LogLevel::Warning)));
+    assert!(logger.can_log_item(&item(LogLevel::Information)));
+    assert!(!logger.level_filter().is_enabled(LogLevel::Warning));
+
+    logger.allow_log_levels(&[LogLevel::Warning]);
+    assert!(logger.can_log_item(&item(LogLevel::Warning)));
+}
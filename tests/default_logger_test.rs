@@ -0,0 +1,162 @@
+mod shared;
+
+use chrono::{DateTime, Duration, Local, TimeZone, Utc};
+use muxide_logging::clock::{MockClock, SystemClock};
+use muxide_logging::format::Format;
+use muxide_logging::log::{LevelFilter, LogItem, LogLevel, Logger, Metadata};
+use muxide_logging::set_clock;
+use muxide_logging::{debug, error, info, trace};
+use muxide_logging::{reset_default_logger, set_default_logger, set_min_level, DEFAULT_LOGGER};
+use shared::*;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Held by each test, as they all install loggers into the same global.
+static INSTALLED: Mutex<()> = Mutex::new(());
+
+/// Records each message, rejecting debug logs.
+struct RecordingLogger {
+    messages: Arc<Mutex<Vec<String>>>,
+}
+
+impl Logger for RecordingLogger {
+    type ReturnType = ();
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return metadata.level() != LogLevel::Debug;
+    }
+
+    fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        self.messages.lock().unwrap().push(item.into_message());
+    }
+}
+
+/// Counts the logs it is asked about, enabling only the levels of its filter.
+struct FilteredLogger {
+    filter: LevelFilter,
+    checked: Arc<AtomicUsize>,
+}
+
+impl Logger for FilteredLogger {
+    type ReturnType = ();
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.checked.fetch_add(1, Ordering::SeqCst);
+
+        return self.filter.is_enabled(metadata.level());
+    }
+
+    fn enabled_levels(&self) -> Option<LevelFilter> {
+        return Some(self.filter.clone());
+    }
+
+    fn log_item<Tz: TimeZone>(&mut self, _item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+    }
+}
+
+#[test]
+fn set_default_logger_test() {
+    let _installed = INSTALLED.lock().unwrap();
+    let messages = Arc::new(Mutex::new(Vec::new()));
+
+    set_min_level(LogLevel::Error).unwrap();
+    set_default_logger(Box::new(RecordingLogger {
+        messages: messages.clone(),
+    }))
+    .unwrap();
+
+    // The default logger's minimum level doesn't apply to the installed logger.
+    info!("Information {}", 1);
+    debug!("Debug {}", 2);
+    assert!(reset_default_logger().unwrap().is_some());
+    info!("Information {}", 3);
+
+    assert_eq!(*messages.lock().unwrap(), vec!["Information 1"]);
+    assert!(!DEFAULT_LOGGER
+        .lock()
+        .unwrap()
+        .level_filter()
+        .is_enabled(LogLevel::Information));
+    assert!(reset_default_logger().unwrap().is_none());
+}
+
+#[test]
+fn installed_levels_test() {
+    let _installed = INSTALLED.lock().unwrap();
+    let filter = LevelFilter::new();
+    let checked = Arc::new(AtomicUsize::new(0));
+
+    filter.set_enabled(vec![LogLevel::Error]);
+    set_default_logger(Box::new(FilteredLogger {
+        filter: filter.clone(),
+        checked: checked.clone(),
+    }))
+    .unwrap();
+
+    // Disabled levels are rejected without asking, or locking, the installed logger.
+    trace!("Trace {}", 1);
+    assert_eq!(checked.load(Ordering::SeqCst), 0);
+    error!("Error {}", 2);
+    assert_eq!(checked.load(Ordering::SeqCst), 1);
+
+    // Levels the logger enables after it is installed are passed on to it.
+    filter.set_enabled(vec![LogLevel::Error, LogLevel::Trace]);
+    trace!("Trace {}", 3);
+    assert_eq!(checked.load(Ordering::SeqCst), 2);
+
+    assert!(reset_default_logger().unwrap().is_some());
+}
+
+#[test]
+fn locked_logger_timestamp_test() {
+    let _installed = INSTALLED.lock().unwrap();
+    let time = Utc.ymd(2021, 6, 1).and_hms(12, 0, 0);
+    let clock = MockClock::new(time);
+
+    if Path::new(DEFAULT_LOGGER_TEST_FILE_NAME).exists() {
+        std::fs::remove_file(DEFAULT_LOGGER_TEST_FILE_NAME).unwrap();
+    }
+
+    set_clock(clock.clone()).unwrap();
+    let mut logger = DEFAULT_LOGGER.lock().unwrap();
+    logger.set_override(Format::parse("{time:%F %T} {message}").unwrap());
+    logger.open_file(DEFAULT_LOGGER_TEST_FILE_NAME).unwrap();
+
+    let handle = thread::spawn(|| {
+        error!(TEST_ERROR_MESSAGE);
+    });
+
+    // The log is timestamped when it is created, not once the thread holding the logger is done.
+    thread::sleep(std::time::Duration::from_millis(200));
+    clock.advance(Duration::hours(1));
+    drop(logger);
+    handle.join().unwrap();
+
+    DEFAULT_LOGGER.lock().unwrap().close_file();
+    set_clock(SystemClock).unwrap();
+
+    assert_eq!(
+        std::fs::read_to_string(DEFAULT_LOGGER_TEST_FILE_NAME).unwrap(),
+        format!(
+            "{} {}\n",
+            time.with_timezone(&Local).format("%F %T"),
+            TEST_ERROR_MESSAGE
+        )
+    );
+
+    std::fs::remove_file(DEFAULT_LOGGER_TEST_FILE_NAME).unwrap();
+}
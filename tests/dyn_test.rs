@@ -0,0 +1,59 @@
+use chrono::{Local, Utc};
+use muxide_logging::format::{Format, FormatItem};
+use muxide_logging::info;
+use muxide_logging::log::{DynLogger, LogItem, LogLevel, Logger};
+use muxide_logging::logger::{FileLogger, MultiLogger, Sink, StringLogger};
+use std::sync::{Arc, Mutex};
+
+fn message_logger() -> StringLogger<Local> {
+    let mut logger = StringLogger::new();
+    logger.set_override(Format::new().append(FormatItem::LogString));
+
+    return logger;
+}
+
+#[test]
+fn pointer_logger_test() {
+    let mut logger = message_logger();
+    let mut boxed = Box::new(message_logger());
+    let mut shared = Arc::new(Mutex::new(message_logger()));
+
+    assert_eq!(
+        info!(logger: &mut logger, "By reference").unwrap(),
+        "By reference"
+    );
+    assert_eq!(info!(logger: boxed, "Boxed").unwrap(), "Boxed");
+    assert_eq!(info!(logger: shared, "Shared").unwrap(), "Shared");
+    assert_eq!(
+        shared.log_item(LogItem::new(Format::default(), LogLevel::Error, "Item")),
+        "Item"
+    );
+}
+
+#[test]
+fn trait_object_test() {
+    let mut file_logger = FileLogger::<Utc>::new();
+    file_logger.set_min_level(LogLevel::Error);
+
+    let loggers: Vec<Box<dyn DynLogger + Send>> = vec![
+        Box::new(message_logger()),
+        Box::new(StringLogger::<Utc>::new_tz()),
+        Box::new(file_logger),
+    ];
+
+    assert!(loggers[0].can_log_dyn(
+        &LogItem::new(Format::default(), LogLevel::Information, "").into_fixed_offset()
+    ));
+    assert!(!loggers[2].can_log_dyn(
+        &LogItem::new(Format::default(), LogLevel::Information, "").into_fixed_offset()
+    ));
+
+    // Boxed trait objects are loggers themselves, so can be nested in other loggers.
+    let mut logger = MultiLogger::new();
+
+    for child in loggers {
+        logger.add(Sink::new(child));
+    }
+
+    info!(logger: logger, "Log message");
+}
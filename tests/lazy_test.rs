@@ -0,0 +1,78 @@
+use chrono::{DateTime, Local, TimeZone, Utc};
+use muxide_logging::log::{LogItem, LogLevel, Logger, Metadata};
+use muxide_logging::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static EVALUATED: AtomicUsize = AtomicUsize::new(0);
+
+fn evaluate(message: &'static str) -> &'static str {
+    EVALUATED.fetch_add(1, Ordering::SeqCst);
+
+    return message;
+}
+
+/// Rejects every log in the pre-check and panics if anything past it is reached.
+struct DisabledLogger;
+
+impl Logger for DisabledLogger {
+    type ReturnType = ();
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        assert_eq!(metadata.module_path(), module_path!());
+
+        return false;
+    }
+
+    fn can_log_item<Tz: TimeZone>(&self, _item: &LogItem<Tz>) -> bool
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        panic!("can_log_item called for a disabled log");
+    }
+
+    fn log_item<Tz: TimeZone>(&mut self, _item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        panic!("log_item called for a disabled log");
+    }
+}
+
+#[test]
+fn lazy_message_test() {
+    set_min_level(LogLevel::Warning).unwrap();
+    restrict_log_levels(&[LogLevel::Error]).unwrap();
+
+    // None of the message expressions, format arguments or field values are evaluated.
+    assert_eq!(info!(evaluate("message")), None);
+    assert_eq!(info!("{}", evaluate("argument")), None);
+    assert_eq!(debug!("message", field = evaluate("field")), None);
+    assert_eq!(error!(evaluate("message")), None);
+    assert_eq!(EVALUATED.load(Ordering::SeqCst), 0);
+
+    let mut logger = DisabledLogger;
+    assert_eq!(
+        critical!(logger: logger, "{}", evaluate("argument"), field = evaluate("field")),
+        None
+    );
+    assert_eq!(critical!(evaluate("message"), logger), None);
+    assert_eq!(EVALUATED.load(Ordering::SeqCst), 0);
+
+    // Enabled levels are still built.
+    assert_eq!(warning!(evaluate("message")), Some(()));
+    assert_eq!(EVALUATED.load(Ordering::SeqCst), 1);
+
+    allow_log_levels(&[LogLevel::Error]).unwrap();
+    assert_eq!(error!("{}", evaluate("argument")), Some(()));
+    assert_eq!(EVALUATED.load(Ordering::SeqCst), 2);
+
+    clear_min_level().unwrap();
+    assert_eq!(info!("message", field = evaluate("field")), Some(()));
+    assert_eq!(EVALUATED.load(Ordering::SeqCst), 3);
+}
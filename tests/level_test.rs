@@ -0,0 +1,91 @@
+use chrono::Local;
+use muxide_logging::directive::Directives;
+use muxide_logging::format::Format;
+use muxide_logging::log::{LogItem, LogLevel, Logger, Metadata};
+use muxide_logging::logger::FileLogger;
+
+fn can_log(logger: &FileLogger<Local>, level: LogLevel) -> bool {
+    return logger.can_log_item(&LogItem::new(Format::default(), level, ""));
+}
+
+#[test]
+fn level_order_test() {
+    assert!(LogLevel::Trace < LogLevel::Debug);
+    assert!(LogLevel::Debug < LogLevel::Information);
+    assert!(LogLevel::Information < LogLevel::StateChange);
+    assert!(LogLevel::StateChange < LogLevel::Warning);
+    assert!(LogLevel::Warning < LogLevel::Error);
+    assert!(LogLevel::Error < LogLevel::Critical);
+    assert_eq!(LogLevel::Error.max(LogLevel::Information), LogLevel::Error);
+}
+
+#[test]
+fn min_level_test() {
+    let mut logger = FileLogger::<Local>::new();
+    let filter = logger.level_filter();
+
+    logger.set_min_level(LogLevel::Warning);
+    assert!(!filter.is_enabled(LogLevel::Information));
+    assert!(filter.is_enabled(LogLevel::Warning));
+    assert!(!can_log(&logger, LogLevel::Trace));
+    assert!(!can_log(&logger, LogLevel::Debug));
+    assert!(!can_log(&logger, LogLevel::Information));
+    assert!(!can_log(&logger, LogLevel::StateChange));
+    assert!(can_log(&logger, LogLevel::Warning));
+    assert!(can_log(&logger, LogLevel::Error));
+    assert!(can_log(&logger, LogLevel::Critical));
+
+    // Restrictions still apply above the threshold and allowing a level doesn't override it.
+    logger.restrict_log_levels(&[LogLevel::Error]);
+    assert!(!can_log(&logger, LogLevel::Error));
+    logger.allow_log_levels(&[LogLevel::Error, LogLevel::Information]);
+    assert!(can_log(&logger, LogLevel::Error));
+    assert!(!can_log(&logger, LogLevel::Information));
+
+    logger.clear_min_level();
+    assert!(can_log(&logger, LogLevel::Information));
+    assert!(filter.is_enabled(LogLevel::Information));
+}
+
+#[test]
+fn directives_test() {
+    let mut logger = FileLogger::<Local>::new();
+    let filter = logger.level_filter();
+    let enabled = |logger: &FileLogger<Local>, level, module_path| {
+        return logger.enabled(&Metadata::new(level, module_path));
+    };
+
+    logger.set_min_level(LogLevel::Error);
+    logger.set_directives(Directives::parse("muxide::pty=information").unwrap());
+    assert!(enabled(
+        &logger,
+        LogLevel::Information,
+        "muxide::pty::reader"
+    ));
+    assert!(!enabled(&logger, LogLevel::Debug, "muxide::pty::reader"));
+    assert!(!enabled(&logger, LogLevel::Warning, "muxide::render"));
+    assert!(enabled(&logger, LogLevel::Error, "muxide::render"));
+    assert!(filter.is_enabled(LogLevel::Information));
+    assert!(!filter.is_enabled(LogLevel::Debug));
+
+    // A default directive replaces the minimum level for unmatched modules.
+    logger.set_directives(Directives::parse("warning,muxide::pty=information").unwrap());
+    assert!(enabled(&logger, LogLevel::Warning, "muxide::render"));
+    assert!(!enabled(&logger, LogLevel::Information, "muxide::render"));
+
+    let item = LogItem::new(
+        Format::default().set_module_path("muxide::pty"),
+        LogLevel::Information,
+        "",
+    );
+    assert!(logger.can_log_item(&item));
+
+    logger.restrict_log_levels(&[LogLevel::Information]);
+    assert!(!logger.can_log_item(&item));
+    assert!(!filter.is_enabled(LogLevel::Information));
+
+    logger.clear_directives();
+    logger.allow_log_levels(&[LogLevel::Information]);
+    assert!(!logger.can_log_item(&item));
+    assert!(!filter.is_enabled(LogLevel::Warning));
+}
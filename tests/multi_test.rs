@@ -0,0 +1,100 @@
+use chrono::{DateTime, Local, TimeZone, Utc};
+use muxide_logging::format::{Format, FormatItem};
+use muxide_logging::log::{LogItem, LogLevel, Logger};
+use muxide_logging::logger::{MultiLogger, Sink};
+use muxide_logging::{error, info, warning};
+use std::sync::{Arc, Mutex};
+
+/// Records each built log.
+struct RecordingLogger {
+    logs: Arc<Mutex<Vec<String>>>,
+}
+
+impl Logger for RecordingLogger {
+    type ReturnType = ();
+
+    fn log_item<Tz: TimeZone>(&mut self, item: LogItem<Tz>) -> Self::ReturnType
+    where
+        Tz::Offset: std::fmt::Display,
+        DateTime<Local>: From<DateTime<Tz>>,
+        DateTime<Utc>: From<DateTime<Tz>>,
+        DateTime<Tz>: Copy,
+    {
+        self.logs.lock().unwrap().push(item.into());
+    }
+}
+
+fn recording_logger() -> (RecordingLogger, Arc<Mutex<Vec<String>>>) {
+    let logs = Arc::new(Mutex::new(Vec::new()));
+
+    return (RecordingLogger { logs: logs.clone() }, logs);
+}
+
+#[test]
+fn fan_out_test() {
+    let (file, file_logs) = recording_logger();
+    let (console, console_logs) = recording_logger();
+
+    let mut logger = MultiLogger::new()
+        .with(
+            Sink::new(file).with_override(
+                Format::new()
+                    .append(FormatItem::LogLevel)
+                    .append(FormatItem::CustomString(": ".to_string()))
+                    .append(FormatItem::LogString),
+            ),
+        )
+        .with(
+            Sink::new(console)
+                .with_min_level(LogLevel::Warning)
+                .with_restricted_levels(&[LogLevel::Warning])
+                .with_override(Format::new().append(FormatItem::LogString)),
+        );
+
+    assert_eq!(logger.len(), 2);
+
+    info!(logger: logger, "Information message");
+    warning!(logger: logger, "Warning message");
+    error!(logger: logger, "Error {}", 1);
+
+    assert_eq!(
+        *file_logs.lock().unwrap(),
+        vec![
+            "Information: Information message",
+            "Warning: Warning message",
+            "Error: Error 1"
+        ]
+    );
+    assert_eq!(*console_logs.lock().unwrap(), vec!["Error 1"]);
+}
+
+#[test]
+fn all_sinks_disabled_test() {
+    let (child, logs) = recording_logger();
+    let mut logger = MultiLogger::new().with(Sink::new(child).with_min_level(LogLevel::Critical));
+
+    assert!(!logger.can_log_item(&LogItem::new(Format::default(), LogLevel::Error, "")));
+    error!(logger: logger, "Error message");
+    assert!(logs.lock().unwrap().is_empty());
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn override_daylight_saving_test() {
+    use muxide_logging::chrono_tz::Australia::Adelaide;
+    use muxide_logging::clock::MockClock;
+
+    let (child, logs) = recording_logger();
+    // Daylight saving ends in Adelaide at 16:30 UTC, moving the offset from +10:30 to +09:30.
+    let clock = MockClock::new(Utc.ymd(2021, 4, 3).and_hms(16, 0, 0));
+    let mut logger = MultiLogger::new().with(
+        Sink::new(child).with_override(Format::parse_in("{time:%H:%M %:z}", Adelaide).unwrap()),
+    );
+    logger.set_clock(clock.clone());
+
+    info!(logger: logger, "");
+    clock.advance(chrono::Duration::hours(1));
+    info!(logger: logger, "");
+
+    assert_eq!(*logs.lock().unwrap(), vec!["02:30 +10:30", "02:30 +09:30"]);
+}
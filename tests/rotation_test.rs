@@ -0,0 +1,148 @@
+mod shared;
+
+use chrono::Utc;
+use muxide_logging::format::{Format, FormatItem};
+use muxide_logging::logger::FileLogger;
+use muxide_logging::rotation::{backup_path, SizeRotation, TimeRotation};
+use muxide_logging::*;
+use shared::*;
+use std::path::Path;
+use std::time::Duration;
+
+fn remove_test_files() {
+    for path in [
+        Path::new(ROTATION_TEST_FILE_NAME).to_path_buf(),
+        backup_path(Path::new(ROTATION_TEST_FILE_NAME), 1),
+        backup_path(Path::new(ROTATION_TEST_FILE_NAME), 2),
+        backup_path(Path::new(ROTATION_TEST_FILE_NAME), 3),
+    ]
+    .iter()
+    {
+        if path.exists() {
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+}
+
+fn read(index: usize) -> String {
+    let path = match index {
+        0 => Path::new(ROTATION_TEST_FILE_NAME).to_path_buf(),
+        _ => backup_path(Path::new(ROTATION_TEST_FILE_NAME), index),
+    };
+
+    return std::fs::read_to_string(path).unwrap();
+}
+
+#[test]
+fn size_rotation_test() {
+    remove_test_files();
+
+    DEFAULT_LOGGER
+        .lock()
+        .unwrap()
+        .set_override(Format::new().append(FormatItem::LogString));
+
+    // Each log is 6 bytes including the new line so two logs fit in a file.
+    set_output_file_with_rotation(ROTATION_TEST_FILE_NAME, SizeRotation::new(12, 2)).unwrap();
+
+    for i in 0..7 {
+        info!(&format!("log {}", i));
+    }
+
+    close_output_file().unwrap();
+
+    assert_eq!(read(0), "log 6\n");
+    assert_eq!(read(1), "log 4\nlog 5\n");
+    assert_eq!(read(2), "log 2\nlog 3\n");
+    assert!(!backup_path(Path::new(ROTATION_TEST_FILE_NAME), 3).exists());
+
+    // Reopening continues from the existing size of the file.
+    set_output_file_with_rotation(ROTATION_TEST_FILE_NAME, SizeRotation::new(12, 2)).unwrap();
+    info!("log 7");
+    info!("log 8");
+    close_output_file().unwrap();
+
+    assert_eq!(read(0), "log 8\n");
+    assert_eq!(read(1), "log 6\nlog 7\n");
+    assert_eq!(read(2), "log 4\nlog 5\n");
+
+    remove_test_files();
+}
+
+#[test]
+fn time_rotation_test() {
+    let pattern = "time_rotation_test-%Y-%m-%d.log";
+    let expired = "time_rotation_test-2000-01-01.log";
+    let unrelated = "time_rotation_test-other.log";
+
+    for name in [expired, unrelated].iter() {
+        std::fs::write(name, "").unwrap();
+    }
+
+    let mut logger = FileLogger::<Utc>::new();
+    logger.set_override(Format::new_tz().append(FormatItem::LogString));
+    logger
+        .open_file_with_time_rotation(
+            pattern,
+            TimeRotation::daily().with_retention(Duration::from_secs(24 * 60 * 60)),
+        )
+        .unwrap();
+
+    let path = logger.path().unwrap().to_path_buf();
+    assert_eq!(
+        path,
+        Path::new(&Utc::now().format(pattern).to_string()).to_path_buf()
+    );
+
+    info!(logger: logger, "first");
+    info!(logger: logger, "second");
+    logger.close_file();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\nsecond\n");
+    assert!(!Path::new(expired).exists());
+    assert!(Path::new(unrelated).exists());
+
+    std::fs::remove_file(path).unwrap();
+    std::fs::remove_file(unrelated).unwrap();
+}
+
+#[test]
+fn reopen_after_failed_rotation_test() {
+    let directory = Path::new("rotation_reopen_test");
+    let path = directory.join("reopen.log");
+
+    if directory.exists() {
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+
+    std::fs::create_dir(directory).unwrap();
+
+    let mut logger = FileLogger::<Utc>::new();
+    logger.set_override(Format::new_tz().append(FormatItem::LogString));
+    logger
+        .open_file_with_rotation(&path, SizeRotation::new(12, 1))
+        .unwrap();
+
+    info!(logger: logger, "log 0");
+    info!(logger: logger, "log 1");
+
+    // Removing the directory makes both rotating and reopening the file fail.
+    std::fs::remove_dir_all(directory).unwrap();
+    info!(logger: logger, "log 2");
+    assert_eq!(logger.path(), Some(path.as_path()));
+
+    // The file is reopened by the next log once it can be.
+    std::fs::create_dir(directory).unwrap();
+    info!(logger: logger, "log 3");
+    info!(logger: logger, "log 4");
+    info!(logger: logger, "log 5");
+    logger.close_file();
+
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "log 5\n");
+    assert_eq!(
+        std::fs::read_to_string(backup_path(&path, 1)).unwrap(),
+        "log 3\nlog 4\n"
+    );
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
@@ -1,10 +1,27 @@
 // Shared constants from the tests
+#![allow(clippy::redundant_static_lifetimes)]
 
 #[allow(dead_code)]
 pub const TEST_FILE_NAME: &'static str = "file_test.log";
 #[allow(dead_code)]
 pub const THREADED_TEST_FILE_NAME: &'static str = "threaded_file_test.log";
+#[allow(dead_code)]
 pub const TEST_ERROR_MESSAGE: &'static str = "Error message";
+#[allow(dead_code)]
 pub const TEST_WARNING_MESSAGE: &'static str = "Warning message";
+#[allow(dead_code)]
 pub const TEST_STATE_CHANGE_MESSAGE: &'static str = "State change message";
+#[allow(dead_code)]
 pub const TEST_INFORMATION_MESSAGE: &'static str = "Information message";
+#[allow(dead_code)]
+pub const ROTATION_TEST_FILE_NAME: &'static str = "rotation_test.log";
+#[allow(dead_code)]
+pub const STYLE_TEST_FILE_NAME: &'static str = "style_test.log";
+#[allow(dead_code)]
+pub const CLOCK_TEST_FILE_NAME: &'static str = "clock_test.log";
+#[allow(dead_code)]
+pub const ALLOCATION_TEST_FILE_NAME: &'static str = "allocation_test.log";
+#[allow(dead_code)]
+pub const DEFAULT_LOGGER_TEST_FILE_NAME: &'static str = "default_logger_test.log";
+#[allow(dead_code)]
+pub const MACRO_ALLOCATION_TEST_FILE_NAME: &'static str = "macro_allocation_test.log";
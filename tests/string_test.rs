@@ -1,7 +1,7 @@
 mod shared;
 
 use chrono::{DateTime, Utc};
-use muxide_logging::format::Format;
+use muxide_logging::format::{Encoding, Format};
 use muxide_logging::logger::StringLogger;
 use muxide_logging::*;
 use shared::*;
@@ -75,3 +75,25 @@ fn test_info_macro() {
         )
     );
 }
+
+#[test]
+fn test_json_encoding() {
+    let mut logger = create_string_logger();
+    logger.set_encoding(Encoding::Json);
+
+    let res = warning!(logger: logger, "tab\there \"quoted\"", pane = 2).unwrap();
+
+    assert_eq!(
+        res,
+        format!(
+            concat!(
+                r#"{{"timestamp":"2003-07-01T10:52:37+00:00","level":"Warning","#,
+                r#""message":"tab\there \"quoted\"","module_path":"{}","file":"{}","#,
+                r#""line":{},"column":15,"fields":{{"pane":2}}}}"#
+            ),
+            module_path!(),
+            file!(),
+            line!() - 12,
+        )
+    );
+}
@@ -0,0 +1,51 @@
+mod shared;
+
+use chrono::Local;
+use muxide_logging::format::Format;
+use muxide_logging::log::{LogItem, LogLevel, Logger};
+use muxide_logging::logger::{FileLogger, StringLogger};
+use muxide_logging::style::ColourMode;
+use shared::*;
+use std::path::Path;
+
+const PATTERN: &str = "{level_colour}{level}{reset}: {style:bold}{message}{reset}";
+
+#[test]
+fn file_colour_test() {
+    if Path::new(STYLE_TEST_FILE_NAME).exists() {
+        std::fs::remove_file(STYLE_TEST_FILE_NAME).unwrap();
+    }
+
+    let format = Format::<Local>::parse(PATTERN)
+        .unwrap()
+        .set_colour_mode(ColourMode::Always);
+    let mut logger = FileLogger::<Local>::new();
+    let mut string_logger = StringLogger::new();
+
+    logger.set_override(format.clone());
+    string_logger.set_override(format);
+    logger.open_file(STYLE_TEST_FILE_NAME).unwrap();
+
+    logger.log_item(LogItem::new(
+        Format::default(),
+        LogLevel::Error,
+        TEST_ERROR_MESSAGE,
+    ));
+    logger.close_file();
+
+    // The same format is coloured elsewhere but never in the file.
+    assert_eq!(
+        string_logger.log_item(LogItem::new(
+            Format::default(),
+            LogLevel::Error,
+            TEST_ERROR_MESSAGE
+        )),
+        format!("\x1b[31mError\x1b[0m: \x1b[1m{}\x1b[0m", TEST_ERROR_MESSAGE)
+    );
+    assert_eq!(
+        std::fs::read_to_string(STYLE_TEST_FILE_NAME).unwrap(),
+        format!("Error: {}\n", TEST_ERROR_MESSAGE)
+    );
+
+    std::fs::remove_file(STYLE_TEST_FILE_NAME).unwrap();
+}
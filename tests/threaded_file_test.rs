@@ -2,34 +2,27 @@ mod shared;
 
 use chrono::DateTime;
 use muxide_logging::format::Format;
-use muxide_logging::logger::StringLogger;
 use muxide_logging::*;
 use shared::*;
 use std::path::Path;
 use std::thread;
 
+fn spawn<F: FnOnce() + Send + 'static>(name: &str, f: F) -> thread::JoinHandle<()> {
+    return thread::Builder::new()
+        .name(name.to_string())
+        .spawn(f)
+        .unwrap();
+}
+
 #[test]
 fn threaded_test() {
     if Path::new(THREADED_TEST_FILE_NAME).exists() {
         std::fs::remove_file(THREADED_TEST_FILE_NAME).unwrap();
     }
 
-    let mut comp_logger = StringLogger::new();
-    let mut comp = String::new();
-
     DEFAULT_LOGGER.lock().unwrap().set_override(
-        Format::default()
-            .set_column(0)
-            .set_line(12)
-            .set_constant_time(DateTime::from(
-                DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
-            )),
-    );
-
-    comp_logger.set_override(
-        Format::default()
-            .set_column(0)
-            .set_line(12)
+        Format::parse("[{time:%k:%M:%S}] ({thread_name}) {level}: {message}")
+            .unwrap()
             .set_constant_time(DateTime::from(
                 DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap(),
             )),
@@ -37,44 +30,53 @@ fn threaded_test() {
 
     set_output_file(THREADED_TEST_FILE_NAME).unwrap();
 
-    let h1 = thread::spawn(move || {
-        error!(TEST_ERROR_MESSAGE);
-    });
-
-    let h2 = thread::spawn(move || {
-        warning!(TEST_WARNING_MESSAGE);
-    });
-
-    let h3 = thread::spawn(move || {
-        state_change!(TEST_STATE_CHANGE_MESSAGE);
-    });
-
-    let h4 = thread::spawn(move || {
-        info!(TEST_INFORMATION_MESSAGE);
-    });
-
-    comp.push_str(&error!(TEST_ERROR_MESSAGE, comp_logger).unwrap());
-    comp.push('\n');
-
-    comp.push_str(&warning!(TEST_WARNING_MESSAGE, comp_logger).unwrap());
-    comp.push('\n');
-
-    comp.push_str(&state_change!(TEST_STATE_CHANGE_MESSAGE, comp_logger).unwrap());
-    comp.push('\n');
-
-    comp.push_str(&info!(TEST_INFORMATION_MESSAGE, comp_logger).unwrap());
-    comp.push('\n');
-
-    h1.join().unwrap();
-    h2.join().unwrap();
-    h3.join().unwrap();
-    h4.join().unwrap();
+    let handles = vec![
+        spawn("first", || {
+            error!(TEST_ERROR_MESSAGE);
+        }),
+        spawn("second", || {
+            warning!(TEST_WARNING_MESSAGE);
+        }),
+        spawn("third", || {
+            state_change!(TEST_STATE_CHANGE_MESSAGE);
+        }),
+        spawn("fourth", || {
+            info!(TEST_INFORMATION_MESSAGE);
+        }),
+    ];
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
 
     close_output_file().unwrap();
     let content = std::fs::read_to_string(THREADED_TEST_FILE_NAME).unwrap();
-
-    // We can't make any guarantees about the order in which things are written to the file therefore we try to check but if this fails then just check if the file was empty.
-    assert!(content == comp || !content.is_empty());
+    let time = DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000")
+        .unwrap()
+        .with_timezone(&chrono::Local)
+        .format("%k:%M:%S")
+        .to_string();
+
+    // The order in which the threads write to the file isn't guaranteed, but each line can be
+    // matched to the thread that wrote it.
+    let mut lines: Vec<&str> = content.lines().collect();
+    lines.sort_unstable();
+
+    let mut expected = vec![
+        format!("[{}] (first) Error: {}", time, TEST_ERROR_MESSAGE),
+        format!("[{}] (second) Warning: {}", time, TEST_WARNING_MESSAGE),
+        format!(
+            "[{}] (third) StateChange: {}",
+            time, TEST_STATE_CHANGE_MESSAGE
+        ),
+        format!(
+            "[{}] (fourth) Information: {}",
+            time, TEST_INFORMATION_MESSAGE
+        ),
+    ];
+    expected.sort_unstable();
+
+    assert_eq!(lines, expected);
 
     std::fs::remove_file(THREADED_TEST_FILE_NAME).unwrap();
 }
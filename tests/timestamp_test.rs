@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use muxide_logging::format::{Encoding, Format};
+use muxide_logging::log::{LogItem, LogLevel, Logger};
+use muxide_logging::logger::StringLogger;
+use std::thread;
+use std::time::Duration;
+
+const TIME_FORMAT: &str = "%H:%M:%S%.9f";
+
+#[test]
+fn creation_time_test() {
+    let item = LogItem::new(
+        Format::<Utc>::parse(&format!("{{time:{}}}", TIME_FORMAT)).unwrap(),
+        LogLevel::Information,
+        "",
+    );
+    let timestamp = item.timestamp();
+
+    // Formatting later still uses the time the item was created.
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(
+        item.clone().build(Encoding::Text),
+        timestamp.format(TIME_FORMAT).to_string()
+    );
+    assert_eq!(
+        item.build(Encoding::Json),
+        format!(
+            r#"{{"timestamp":"{}","level":"Information","message":"","module_path":null,"file":null,"line":null,"column":null}}"#,
+            timestamp.to_rfc3339()
+        )
+    );
+}
+
+#[test]
+fn override_timestamp_test() {
+    let mut logger = StringLogger::<Utc>::new_tz();
+    logger.set_override(Format::parse(&format!("{{time:{}}}", TIME_FORMAT)).unwrap());
+
+    let item = LogItem::new(Format::<Utc>::new_tz(), LogLevel::Information, "");
+    let timestamp = item.timestamp();
+
+    thread::sleep(Duration::from_millis(10));
+    assert_eq!(
+        logger.log_item(item),
+        timestamp.format(TIME_FORMAT).to_string()
+    );
+}
+
+#[test]
+fn constant_time_test() {
+    let time: DateTime<Utc> =
+        DateTime::from(DateTime::parse_from_rfc2822("Tue, 1 Jul 2003 10:52:37 +0000").unwrap());
+    let item = LogItem::new(
+        Format::<Utc>::parse("{time:%H:%M:%S}")
+            .unwrap()
+            .set_constant_time(time),
+        LogLevel::Information,
+        "",
+    );
+
+    assert_ne!(item.timestamp(), time);
+    assert_eq!(item.build(Encoding::Text), "10:52:37");
+}
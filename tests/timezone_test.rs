@@ -0,0 +1,80 @@
+use chrono::{FixedOffset, Local, Offset, Utc};
+use muxide_logging::format::Format;
+use muxide_logging::info;
+use muxide_logging::log::{LogItem, LogLevel, Logger};
+use muxide_logging::logger::{FileLogger, StringLogger};
+
+#[test]
+fn fixed_offset_test() {
+    let offset = FixedOffset::west(3 * 3600 + 1800);
+    let mut logger = StringLogger::new_in(offset);
+
+    logger.set_override(Format::parse_in("{time:%:z} {message}", offset).unwrap());
+    assert_eq!(
+        info!(logger: logger, "Log message").unwrap(),
+        "-03:30 Log message"
+    );
+
+    // Without an override the format from the macros is converted into the logger's timezone.
+    let mut logger = StringLogger::new_in(offset);
+    let item = LogItem::new(
+        Format::<Utc>::parse("{time:%:z}").unwrap(),
+        LogLevel::Information,
+        "",
+    );
+    assert_eq!(logger.log_item(item), "-03:30");
+
+    let logger = FileLogger::new_in(offset);
+    assert!(logger.can_log_item(&LogItem::new(Format::default(), LogLevel::Error, "")));
+}
+
+#[test]
+fn fixed_offset_default_test() {
+    let local = Local::now().offset().fix().to_string();
+
+    assert_eq!(
+        Format::<FixedOffset>::new_tz().timezone().to_string(),
+        local
+    );
+    assert_eq!(
+        "{time:%:z}"
+            .parse::<Format<FixedOffset>>()
+            .unwrap()
+            .build_string(LogLevel::Information, ""),
+        local
+    );
+
+    let mut logger = StringLogger::<FixedOffset>::new_tz();
+    logger.set_override(Format::<FixedOffset>::parse("{time:%:z}").unwrap());
+    assert_eq!(info!(logger: logger, "").unwrap(), local);
+}
+
+#[test]
+fn utc_now_test() {
+    assert_eq!(
+        Format::<Utc>::parse("{time:%:z}")
+            .unwrap()
+            .build_string(LogLevel::Information, ""),
+        "+00:00"
+    );
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn named_timezone_test() {
+    use chrono::TimeZone;
+    use muxide_logging::chrono_tz::Australia::Adelaide;
+
+    let mut logger = StringLogger::new_in(Adelaide);
+    logger.set_override(
+        Format::new_with_constant_time(Adelaide.ymd(2021, 1, 1).and_hms(9, 30, 0)).append(
+            muxide_logging::format::FormatItem::TimeString("%H:%M %Z".to_string()),
+        ),
+    );
+
+    assert_eq!(info!(logger: logger, "").unwrap(), "09:30 ACDT");
+    assert_eq!(
+        Format::<muxide_logging::chrono_tz::Tz>::new_tz().timezone(),
+        &muxide_logging::chrono_tz::UTC
+    );
+}
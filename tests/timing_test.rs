@@ -0,0 +1,72 @@
+use chrono::{Duration, TimeZone, Utc};
+use muxide_logging::clock::MockClock;
+use muxide_logging::format::{Encoding, Format};
+use muxide_logging::info;
+use muxide_logging::log::{LogItem, LogLevel};
+use muxide_logging::logger::StringLogger;
+
+fn timing_logger(clock: &MockClock) -> StringLogger<Utc> {
+    let mut logger = StringLogger::new_tz();
+    logger.set_clock(clock.clone());
+    logger.set_override(Format::parse("{elapsed:ms} {since_last:ms} {message}").unwrap());
+
+    return logger;
+}
+
+#[test]
+fn elapsed_test() {
+    let clock = MockClock::new(Utc.ymd(2021, 1, 1).and_hms(9, 30, 0));
+    let mut logger = timing_logger(&clock);
+
+    clock.advance(Duration::milliseconds(250));
+    assert_eq!(info!(logger: logger, "first").unwrap(), "0.250  first");
+
+    clock.advance(Duration::milliseconds(1500));
+    assert_eq!(
+        info!(logger: logger, "second").unwrap(),
+        "1.750 1.500 second"
+    );
+
+    clock.advance(Duration::seconds(60));
+    assert_eq!(
+        info!(logger: logger, "third").unwrap(),
+        "61.750 60.000 third"
+    );
+
+    // Each logger measures from its own start and its own previous log.
+    let mut other = timing_logger(&clock);
+    assert_eq!(info!(logger: other, "other").unwrap(), "0.000  other");
+}
+
+#[test]
+fn sequence_test() {
+    let mut logger = StringLogger::<Utc>::new_tz();
+    logger.set_override(Format::parse("{sequence}").unwrap());
+
+    let first: u64 = info!(logger: logger, "").unwrap().parse().unwrap();
+    let second: u64 = info!(logger: logger, "").unwrap().parse().unwrap();
+    assert!(second > first);
+
+    // The sequence number is assigned when the item is created, not when it is logged.
+    let earlier = LogItem::new(Format::<Utc>::new_tz(), LogLevel::Information, "");
+    let later = LogItem::new(Format::<Utc>::new_tz(), LogLevel::Information, "");
+    assert!(later.sequence() > earlier.sequence());
+
+    let sequence = earlier.sequence();
+    let item = earlier.with_format(Format::parse("{sequence}").unwrap());
+    assert_eq!(item.build(Encoding::Text), sequence.to_string());
+}
+
+#[test]
+fn json_timing_test() {
+    let clock = MockClock::new(Utc.ymd(2021, 1, 1).and_hms(9, 30, 0));
+    let mut logger = timing_logger(&clock);
+    logger.set_encoding(Encoding::Json);
+
+    let first = info!(logger: logger, "first").unwrap();
+    assert!(first.contains(r#""elapsed":0.000,"since_last":null"#));
+
+    clock.advance(Duration::seconds(2));
+    let second = info!(logger: logger, "second").unwrap();
+    assert!(second.contains(r#""elapsed":2.000,"since_last":2.000"#));
+}